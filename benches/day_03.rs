@@ -0,0 +1,63 @@
+//! Compares the grid-based day 3 solver against the streaming one on a generated schematic.
+//! Run with `cargo bench --bench day_03 -- [rows]`.
+
+use std::{env, time::Instant};
+
+use advent_of_code_2023::solutions::{
+    day_03::{
+        rules::SchematicRules,
+        streaming::{stream_totals, StreamTotals},
+        DayThreeSolver,
+    },
+    Solver,
+};
+
+const WIDTH: usize = 140;
+
+// xorshift, so runs are repeatable without pulling in a rand crate
+fn generate(rows: usize) -> Vec<String> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    (0..rows)
+        .map(|_| {
+            (0..WIDTH)
+                .map(|_| match next() % 20 {
+                    0 => '*',
+                    1 => '#',
+                    2..=6 => char::from(b'0' + (next() % 10) as u8),
+                    _ => '.',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn main() {
+    let rows = env::args()
+        .skip(1)
+        .find_map(|a| a.parse().ok())
+        .unwrap_or(20_000);
+    let lines = generate(rows);
+    let input: Vec<&str> = lines.iter().map(|s| s.as_str()).collect();
+    println!("day 3: {} rows x {} columns", rows, WIDTH);
+
+    let solver = DayThreeSolver {};
+    let start = Instant::now();
+    let grid = StreamTotals {
+        part_sum: solver.part_1(&input).unwrap().parse().unwrap(),
+        gear_ratio_sum: solver.part_2(&input).unwrap().parse().unwrap(),
+    };
+    println!("  schematic (both parts): {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let streamed = stream_totals(&input, &SchematicRules::default());
+    println!("  streaming (single pass): {:?}", start.elapsed());
+
+    assert_eq!(grid, streamed);
+}
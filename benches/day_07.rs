@@ -0,0 +1,66 @@
+//! Compares ranking day 7 hands by their precomputed strength against comparing them by
+//! classifying both hands on every comparison. Run with `cargo bench --bench day_07 -- [hands]`.
+
+use std::{cmp::Ordering, env, time::Instant};
+
+use advent_of_code_2023::solutions::day_07::{rules::RuleSet, Hand, HandType, Rank};
+
+// xorshift, so runs are repeatable without pulling in a rand crate
+fn generate(hands: usize, rules: &RuleSet) -> Vec<Hand> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    (0..hands)
+        .map(|_| {
+            let cards = (0..rules.hand_size)
+                .map(|_| rules.order[next() as usize % rules.order.len()].clone())
+                .collect();
+            Hand::new(cards, next() % 1000 + 1, rules).unwrap()
+        })
+        .collect()
+}
+
+// how hands used to be compared, before they carried a strength
+fn compare_by_classifying(a: &Hand, b: &Hand, rules: &RuleSet) -> Ordering {
+    let strengths =
+        |cards: &[Rank]| -> Vec<u32> { cards.iter().map(|c| rules.strength(c).unwrap()).collect() };
+
+    HandType::classify(&a.cards, rules)
+        .cmp(&HandType::classify(&b.cards, rules))
+        .then_with(|| strengths(&a.cards).cmp(&strengths(&b.cards)))
+}
+
+fn main() {
+    let count = env::args()
+        .skip(1)
+        .find_map(|a| a.parse().ok())
+        .unwrap_or(200_000);
+    println!("day 7: {} hands", count);
+
+    for (name, rules) in [
+        ("standard", RuleSet::default()),
+        ("jokers", RuleSet::jokers()),
+    ] {
+        let start = Instant::now();
+        let hands = generate(count, &rules);
+        println!("  {}: generating and parsing: {:?}", name, start.elapsed());
+
+        let mut by_key = hands.clone();
+        let start = Instant::now();
+        by_key.sort_unstable_by_key(Hand::strength);
+        println!("  {}: strength key sort: {:?}", name, start.elapsed());
+
+        let mut by_classifying = hands;
+        let start = Instant::now();
+        by_classifying.sort_unstable_by(|a, b| compare_by_classifying(a, b, &rules));
+        println!("  {}: classify per comparison: {:?}", name, start.elapsed());
+
+        let strengths = |hands: &[Hand]| hands.iter().map(Hand::strength).collect::<Vec<_>>();
+        assert_eq!(strengths(&by_key), strengths(&by_classifying));
+    }
+}
@@ -0,0 +1,5 @@
+// solvers report failures as `Result<_, ()>` throughout
+#![allow(clippy::result_unit_err)]
+
+pub mod solutions;
+pub mod utils;
@@ -1,20 +1,18 @@
-mod solutions;
-
 use std::{env, fs, path::Path};
 
-use solutions::{
+use advent_of_code_2023::solutions::{
     day_01::DayOneSolver, day_02::DayTwoSolver, day_03::DayThreeSolver, day_04::DayFourSolver,
-    day_05::DayFiveSolver, day_06::DaySixSolver, day_07::DaySevenSolver, Solver,
+    day_05::DayFiveSolver, day_06::DaySixSolver, day_07::DaySevenSolver, day_08::DayEightSolver,
+    day_09::DayNineSolver, normalize_input, Solver,
 };
 
-use crate::solutions::normalize_input;
-
 fn main() {
     let args: Vec<String> = env::args().collect();
 
     let day_part_split = &args[1].split_once('.').unwrap();
     let day = day_part_split.0;
     let part = day_part_split.1;
+    let mode_args: Vec<&str> = args[2..].iter().map(|s| s.as_str()).collect();
 
     let path_string = format!("resources/inputs/day{}.txt", day);
     let input_path = Path::new(&path_string);
@@ -28,13 +26,15 @@ fn main() {
     let solution = match part {
         "1" => solver.part_1(&input).unwrap(),
         "2" => solver.part_2(&input).unwrap(),
-        _ => panic!("unknown part {}", part),
+        mode => solver
+            .run_mode(mode, &mode_args, &input)
+            .unwrap_or_else(|_| panic!("unknown part {}", part)),
     };
 
     println!("Solution is {}", solution);
 }
 
-fn provide_solver(day: &str) -> Box<dyn Solver> {
+fn provide_solver(day: &str) -> Box<dyn Solver<'_>> {
     match day {
         "1" => Box::new(DayOneSolver {}),
         "2" => Box::new(DayTwoSolver {}),
@@ -43,6 +43,8 @@ fn provide_solver(day: &str) -> Box<dyn Solver> {
         "5" => Box::new(DayFiveSolver {}),
         "6" => Box::new(DaySixSolver {}),
         "7" => Box::new(DaySevenSolver {}),
+        "8" => Box::new(DayEightSolver {}),
+        "9" => Box::new(DayNineSolver {}),
         _ => todo!(),
     }
 }
@@ -5,10 +5,17 @@ pub mod day_04;
 pub mod day_05;
 pub mod day_06;
 pub mod day_07;
+pub mod day_08;
+pub mod day_09;
 
 pub trait Solver<'a> {
     fn part_1(&self, input: &'a [&'a str]) -> Result<String, ()>;
     fn part_2(&self, input: &'a [&'a str]) -> Result<String, ()>;
+
+    // extra modes beyond the two parts (e.g. `2.stats`), with any trailing command line args
+    fn run_mode(&self, _mode: &str, _args: &[&str], _input: &'a [&'a str]) -> Result<String, ()> {
+        Err(())
+    }
 }
 
 pub fn normalize_input(input: &str) -> Result<Vec<&str>, ()> {
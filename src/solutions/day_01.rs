@@ -107,7 +107,7 @@ impl Solver<'_> for DayOneSolver {
         };
         let res = input
             .iter()
-            .try_fold(calibration_value, |acc, &c| (acc + c))?;
+            .try_fold(calibration_value, |acc, &c| acc + c)?;
 
         Ok(res.val.to_string())
     }
@@ -119,7 +119,7 @@ impl Solver<'_> for DayOneSolver {
         };
         let res = input
             .iter()
-            .try_fold(calibration_value, |acc, &c| (acc + c))?;
+            .try_fold(calibration_value, |acc, &c| acc + c)?;
 
         Ok(res.val.to_string())
     }
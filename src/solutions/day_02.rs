@@ -2,54 +2,74 @@ use std::str::FromStr;
 
 use super::Solver;
 
-#[derive(Debug)]
-struct Set {
-    red: i64,
-    green: i64,
-    blue: i64,
+use parser::{GameParser, ParseError};
+
+pub mod parser;
+pub mod stats;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
 }
 
-impl FromStr for Set {
-    type Err = ();
+impl Color {
+    pub const ALL: [Color; 3] = [Color::Red, Color::Green, Color::Blue];
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split: Vec<&str> = s.trim().split(',').map(|s| s.trim()).collect();
-
-        let mut red: i64 = 0;
-        let mut green: i64 = 0;
-        let mut blue: i64 = 0;
-
-        for s in split {
-            let mut val_color_split = s.split(' ');
-            let val: i64 = val_color_split.next().unwrap().parse().unwrap();
-            let color = val_color_split.next().unwrap();
-
-            match color {
-                "red" => red = val,
-                "green" => green = val,
-                "blue" => blue = val,
-                _ => return Err(()),
-            }
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Set {
+    pub red: i64,
+    pub green: i64,
+    pub blue: i64,
+}
+
+impl Set {
+    pub fn get(&self, color: Color) -> i64 {
+        match color {
+            Color::Red => self.red,
+            Color::Green => self.green,
+            Color::Blue => self.blue,
+        }
+    }
+
+    pub fn get_mut(&mut self, color: Color) -> &mut i64 {
+        match color {
+            Color::Red => &mut self.red,
+            Color::Green => &mut self.green,
+            Color::Blue => &mut self.blue,
         }
+    }
+
+    pub fn power(&self) -> i64 {
+        self.red * self.green * self.blue
+    }
+}
+
+impl FromStr for Set {
+    type Err = ParseError;
 
-        Ok(Set { red, green, blue })
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GameParser::default().parse_set(s)
     }
 }
 
-struct Game {
-    id: i64,
-    sets: Vec<Set>,
+#[derive(Debug, PartialEq, Eq)]
+pub struct Game {
+    pub id: i64,
+    pub sets: Vec<Set>,
 }
 
 impl Game {
-    fn is_possible(&self, set: &Set) -> bool {
+    pub fn is_possible(&self, set: &Set) -> bool {
         !self
             .sets
             .iter()
             .any(|s| s.red > set.red || s.green > set.green || s.blue > set.blue)
     }
 
-    fn find_max_set(&self) -> Set {
+    pub fn find_max_set(&self) -> Set {
         let mut max_set = Set {
             red: 0,
             green: 0,
@@ -67,36 +87,23 @@ impl Game {
 }
 
 impl FromStr for Game {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.trim().split(": ").map(|s| s.trim());
-
-        let id: i64 = split.next().unwrap().get(5..).unwrap().parse().unwrap();
-        let sets: Vec<Set> = split
-            .next()
-            .unwrap()
-            .split("; ")
-            .map(|s| s.parse().unwrap())
-            .collect();
-
-        Ok(Game { id, sets })
+        GameParser::default().parse_game(s)
     }
 }
 
 pub struct DayTwoSolver {}
 
-impl Solver<'_> for DayTwoSolver {
-    fn part_1<'a>(&self, input: &'a [&'a str]) -> Result<String, ()> {
+impl<'a> Solver<'a> for DayTwoSolver {
+    fn part_1(&self, input: &'a [&'a str]) -> Result<String, ()> {
         let set = Set {
             red: 12,
             green: 13,
             blue: 14,
         };
-        let games = input
-            .iter()
-            .map(|&s| s.parse())
-            .collect::<Result<Vec<Game>, ()>>()?;
+        let games = GameParser::default().parse_games(input).map_err(|_| ())?;
 
         Ok(games
             .iter()
@@ -104,22 +111,24 @@ impl Solver<'_> for DayTwoSolver {
             .to_string())
     }
 
-    fn part_2<'a>(&self, input: &'a [&'a str]) -> Result<String, ()> {
-        let games = input
-            .iter()
-            .map(|&s| s.parse())
-            .collect::<Result<Vec<Game>, ()>>()?;
+    fn part_2(&self, input: &'a [&'a str]) -> Result<String, ()> {
+        let games = GameParser::default().parse_games(input).map_err(|_| ())?;
 
-        let cube_sum: i64 = games
-            .iter()
-            .map(|g| {
-                let max_set = g.find_max_set();
-                max_set.red * max_set.green * max_set.blue
-            })
-            .sum();
+        let cube_sum: i64 = games.iter().map(|g| g.find_max_set().power()).sum();
 
         Ok(cube_sum.to_string())
     }
+
+    fn run_mode(&self, mode: &str, args: &[&str], input: &'a [&'a str]) -> Result<String, ()> {
+        match mode {
+            "stats" => {
+                let games = GameParser::default().parse_games(input).map_err(|_| ())?;
+
+                stats::report(&games, args)
+            }
+            _ => Err(()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,250 @@
+use std::fmt::Display;
+
+use super::{Color, Game, Set};
+
+/// What to do when a single draw mentions the same color more than once, e.g. `1 red, 2 red`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum RepeatPolicy {
+    #[default]
+    Sum,
+    Reject,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseErrorKind {
+    MissingColon,
+    MissingPrefix,
+    InvalidId,
+    NoDraws,
+    InvalidCount,
+    MissingColor,
+    UnknownColor(String),
+    RepeatedColor(Color),
+    UnexpectedToken(String),
+}
+
+/// A parse failure pointing at the offending text. `column` is 1-based and counts bytes,
+/// `line` is only known when parsing a whole input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}, ", line)?;
+        }
+        write!(f, "column {}: ", self.column)?;
+
+        match &self.kind {
+            ParseErrorKind::MissingColon => write!(f, "expected ':' after the game id"),
+            ParseErrorKind::MissingPrefix => write!(f, "expected 'Game'"),
+            ParseErrorKind::InvalidId => write!(f, "invalid game id"),
+            ParseErrorKind::NoDraws => write!(f, "game has no draws"),
+            ParseErrorKind::InvalidCount => write!(f, "invalid cube count"),
+            ParseErrorKind::MissingColor => write!(f, "expected a color after the count"),
+            ParseErrorKind::UnknownColor(c) => write!(f, "unknown color '{}'", c),
+            ParseErrorKind::RepeatedColor(c) => write!(f, "{:?} appears twice in one draw", c),
+            ParseErrorKind::UnexpectedToken(t) => write!(f, "unexpected '{}'", t),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `Game N: ...` lines, tolerating extra whitespace and empty draws or items
+/// left behind by trailing separators.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GameParser {
+    pub repeats: RepeatPolicy,
+}
+
+impl GameParser {
+    pub fn parse_games(&self, lines: &[&str]) -> Result<Vec<Game>, ParseError> {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                self.parse_game(line).map_err(|e| ParseError {
+                    line: Some(i + 1),
+                    ..e
+                })
+            })
+            .collect()
+    }
+
+    pub fn parse_game(&self, line: &str) -> Result<Game, ParseError> {
+        let (header, draws) = line
+            .split_once(':')
+            .ok_or_else(|| error(line, &line[line.len()..], ParseErrorKind::MissingColon))?;
+
+        let header = header.trim();
+        let id = header
+            .strip_prefix("Game")
+            .ok_or_else(|| error(line, header, ParseErrorKind::MissingPrefix))?
+            .trim();
+        let id = id
+            .parse()
+            .map_err(|_| error(line, id, ParseErrorKind::InvalidId))?;
+
+        let sets = draws
+            .split(';')
+            .filter(|draw| !draw.trim().is_empty())
+            .map(|draw| self.parse_draw(line, draw))
+            .collect::<Result<Vec<Set>, ParseError>>()?;
+
+        if sets.is_empty() {
+            return Err(error(line, draws, ParseErrorKind::NoDraws));
+        }
+
+        Ok(Game { id, sets })
+    }
+
+    pub fn parse_set(&self, s: &str) -> Result<Set, ParseError> {
+        self.parse_draw(s, s)
+    }
+
+    // `draw` must be a slice of `line` so errors can be positioned within it
+    fn parse_draw(&self, line: &str, draw: &str) -> Result<Set, ParseError> {
+        let mut set = Set::default();
+        let mut seen = [false; 3];
+
+        for item in draw.split(',').filter(|item| !item.trim().is_empty()) {
+            let mut tokens = item.split_whitespace();
+
+            let count = tokens.next().unwrap_or(item);
+            let count: i64 = count
+                .parse::<u32>()
+                .map_err(|_| error(line, count, ParseErrorKind::InvalidCount))?
+                .into();
+
+            let color = tokens
+                .next()
+                .ok_or_else(|| error(line, &item[item.len()..], ParseErrorKind::MissingColor))?;
+            let parsed_color = match color.to_ascii_lowercase().as_str() {
+                "red" => Color::Red,
+                "green" => Color::Green,
+                "blue" => Color::Blue,
+                _ => {
+                    return Err(error(
+                        line,
+                        color,
+                        ParseErrorKind::UnknownColor(color.to_string()),
+                    ))
+                }
+            };
+
+            if let Some(extra) = tokens.next() {
+                return Err(error(
+                    line,
+                    extra,
+                    ParseErrorKind::UnexpectedToken(extra.to_string()),
+                ));
+            }
+
+            let idx = Color::ALL.iter().position(|&c| c == parsed_color).unwrap();
+            if seen[idx] && self.repeats == RepeatPolicy::Reject {
+                return Err(error(
+                    line,
+                    color,
+                    ParseErrorKind::RepeatedColor(parsed_color),
+                ));
+            }
+            seen[idx] = true;
+            *set.get_mut(parsed_color) += count;
+        }
+
+        Ok(set)
+    }
+}
+
+// `part` is always a subslice of `line`, so its offset can be recovered from the pointers
+fn error(line: &str, part: &str, kind: ParseErrorKind) -> ParseError {
+    ParseError {
+        line: None,
+        column: part.as_ptr() as usize - line.as_ptr() as usize + 1,
+        kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tolerates_whitespace_and_trailing_separators() {
+        let parser = GameParser::default();
+
+        let game = parser
+            .parse_game("  Game   12 :3 blue,4  red ;; 1 red, 2 green, ;")
+            .unwrap();
+
+        assert_eq!(game.id, 12);
+        assert_eq!(
+            game.sets,
+            vec![
+                Set {
+                    red: 4,
+                    green: 0,
+                    blue: 3
+                },
+                Set {
+                    red: 1,
+                    green: 2,
+                    blue: 0
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn repeated_colors_follow_policy() {
+        let line = "Game 1: 1 red, 2 red";
+
+        let summed = GameParser::default().parse_game(line).unwrap();
+        assert_eq!(summed.sets[0].red, 3);
+
+        let rejected = GameParser {
+            repeats: RepeatPolicy::Reject,
+        }
+        .parse_game(line)
+        .unwrap_err();
+        assert_eq!(
+            rejected,
+            ParseError {
+                line: None,
+                column: 18,
+                kind: ParseErrorKind::RepeatedColor(Color::Red)
+            }
+        );
+    }
+
+    #[test]
+    fn errors_are_positioned() {
+        let parser = GameParser::default();
+
+        let err = parser
+            .parse_games(&["Game 1: 1 red", "Game 2: 3 blue, x green"])
+            .unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.column, 17);
+        assert_eq!(err.kind, ParseErrorKind::InvalidCount);
+        assert_eq!(err.to_string(), "line 2, column 17: invalid cube count");
+
+        let err = parser.parse_game("Game 1: 3 purple").unwrap_err();
+        assert_eq!(err.column, 11);
+        assert_eq!(err.kind, ParseErrorKind::UnknownColor("purple".to_string()));
+
+        let err = parser.parse_game("Game one: 3 red").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidId);
+
+        let err = parser.parse_game("Game 1 3 red").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingColon);
+
+        let err = parser.parse_game("Game 1: 3").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingColor);
+    }
+}
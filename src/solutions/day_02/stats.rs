@@ -0,0 +1,369 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use super::{Color, Game, Set};
+
+/// The first draw of a game that needs more cubes of a color than the bag holds.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Violation {
+    pub set_index: usize,
+    pub color: Color,
+    pub drawn: i64,
+    pub available: i64,
+}
+
+impl Game {
+    pub fn first_violation(&self, bag: &Set) -> Option<Violation> {
+        self.sets.iter().enumerate().find_map(|(set_index, set)| {
+            Color::ALL
+                .iter()
+                .find(|&&color| set.get(color) > bag.get(color))
+                .map(|&color| Violation {
+                    set_index,
+                    color,
+                    drawn: set.get(color),
+                    available: bag.get(color),
+                })
+        })
+    }
+}
+
+pub fn impossible_games<'a>(games: &'a [Game], bag: &Set) -> Vec<(&'a Game, Violation)> {
+    games
+        .iter()
+        .filter_map(|g| g.first_violation(bag).map(|v| (g, v)))
+        .collect()
+}
+
+/// The smallest bag each game could have been played with, keyed by game id.
+pub fn minimum_bags(games: &[Game]) -> Vec<(i64, Set)> {
+    games.iter().map(|g| (g.id, g.find_max_set())).collect()
+}
+
+/// How many cubes of a single color were shown per draw, across every game.
+/// A draw that doesn't mention the color counts as showing zero.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Distribution {
+    pub histogram: BTreeMap<i64, usize>,
+}
+
+impl Distribution {
+    pub fn draws(&self) -> usize {
+        self.histogram.values().sum()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.histogram.keys().next().copied()
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.histogram.keys().next_back().copied()
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        let draws = self.draws();
+        if draws == 0 {
+            return None;
+        }
+
+        let total: i64 = self
+            .histogram
+            .iter()
+            .map(|(&val, &count)| val * count as i64)
+            .sum();
+        Some(total as f64 / draws as f64)
+    }
+}
+
+pub fn color_distribution(games: &[Game], color: Color) -> Distribution {
+    let mut distribution = Distribution::default();
+    for set in games.iter().flat_map(|g| &g.sets) {
+        *distribution.histogram.entry(set.get(color)).or_default() += 1;
+    }
+
+    distribution
+}
+
+/// Finds the bag with the fewest total cubes under which at least `k` games are possible,
+/// breaking ties by the smallest power. Returns `None` if there aren't `k` games.
+pub fn tightest_bag(games: &[Game], k: usize) -> Option<Set> {
+    if k == 0 {
+        return Some(Set::default());
+    }
+    if k > games.len() {
+        return None;
+    }
+
+    let min_bags: Vec<Set> = games.iter().map(|g| g.find_max_set()).collect();
+
+    // the optimal bag always takes its red and green counts from some game's minimum bag,
+    // so try every pair of those and pick the k smallest blue requirements that fit
+    let mut reds: Vec<i64> = min_bags.iter().map(|s| s.red).collect();
+    let mut greens: Vec<i64> = min_bags.iter().map(|s| s.green).collect();
+    reds.sort();
+    reds.dedup();
+    greens.sort();
+    greens.dedup();
+
+    let mut best: Option<Set> = None;
+    for &red in &reds {
+        for &green in &greens {
+            let mut blues: Vec<i64> = min_bags
+                .iter()
+                .filter(|s| s.red <= red && s.green <= green)
+                .map(|s| s.blue)
+                .collect();
+            if blues.len() < k {
+                continue;
+            }
+
+            blues.sort();
+            let candidate = Set {
+                red,
+                green,
+                blue: blues[k - 1],
+            };
+
+            let is_better = match &best {
+                None => true,
+                Some(b) => {
+                    let (candidate_total, best_total) = (
+                        candidate.red + candidate.green + candidate.blue,
+                        b.red + b.green + b.blue,
+                    );
+                    candidate_total < best_total
+                        || (candidate_total == best_total && candidate.power() < b.power())
+                }
+            };
+            if is_better {
+                best = Some(candidate);
+            }
+        }
+    }
+
+    best
+}
+
+/// Renders the stats runner mode. Accepts an optional bag as `red green blue` followed by an
+/// optional `k`, defaulting to the part 1 bag and half of the games.
+pub fn report(games: &[Game], args: &[&str]) -> Result<String, ()> {
+    let (bag, k) = match args {
+        [] => (None, None),
+        [red, green, blue] => (Some([red, green, blue]), None),
+        [red, green, blue, k] => (Some([red, green, blue]), Some(k)),
+        _ => return Err(()),
+    };
+
+    let bag = match bag {
+        Some(colors) => {
+            let [red, green, blue] = colors.map(|c| c.parse::<i64>().map_err(|_| ()));
+            Set {
+                red: red?,
+                green: green?,
+                blue: blue?,
+            }
+        }
+        None => Set {
+            red: 12,
+            green: 13,
+            blue: 14,
+        },
+    };
+    let k = match k {
+        Some(k) => k.parse::<usize>().map_err(|_| ())?,
+        None => games.len() / 2,
+    };
+
+    let mut out = String::new();
+    let fmt_err = |_| ();
+
+    writeln!(
+        out,
+        "impossible games for bag {} red, {} green, {} blue:",
+        bag.red, bag.green, bag.blue
+    )
+    .map_err(fmt_err)?;
+    for (game, v) in impossible_games(games, &bag) {
+        writeln!(
+            out,
+            "  game {}: draw {} shows {} {:?} (bag has {})",
+            game.id,
+            v.set_index + 1,
+            v.drawn,
+            v.color,
+            v.available
+        )
+        .map_err(fmt_err)?;
+    }
+
+    writeln!(out, "minimum bags:").map_err(fmt_err)?;
+    for (id, set) in minimum_bags(games) {
+        writeln!(
+            out,
+            "  game {}: {} red, {} green, {} blue (power {})",
+            id,
+            set.red,
+            set.green,
+            set.blue,
+            set.power()
+        )
+        .map_err(fmt_err)?;
+    }
+
+    writeln!(out, "per-color draws:").map_err(fmt_err)?;
+    for color in Color::ALL {
+        let d = color_distribution(games, color);
+        writeln!(
+            out,
+            "  {:?}: min {}, max {}, mean {:.2} over {} draws",
+            color,
+            d.min().unwrap_or(0),
+            d.max().unwrap_or(0),
+            d.mean().unwrap_or(0.0),
+            d.draws()
+        )
+        .map_err(fmt_err)?;
+    }
+
+    match tightest_bag(games, k) {
+        Some(set) => writeln!(
+            out,
+            "tightest bag for {} games: {} red, {} green, {} blue",
+            k, set.red, set.green, set.blue
+        ),
+        None => writeln!(out, "no bag allows {} games", k),
+    }
+    .map_err(fmt_err)?;
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solutions::normalize_input;
+
+    use super::*;
+
+    const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    fn games() -> Vec<Game> {
+        normalize_input(INPUT)
+            .unwrap()
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn impossible_games_report_first_violation() {
+        let games = games();
+        let bag = Set {
+            red: 12,
+            green: 13,
+            blue: 14,
+        };
+
+        let impossible = impossible_games(&games, &bag);
+        let ids: Vec<i64> = impossible.iter().map(|(g, _)| g.id).collect();
+        assert_eq!(ids, vec![3, 4]);
+        assert_eq!(
+            impossible[0].1,
+            Violation {
+                set_index: 0,
+                color: Color::Red,
+                drawn: 20,
+                available: 12
+            }
+        );
+        assert_eq!(
+            impossible[1].1,
+            Violation {
+                set_index: 2,
+                color: Color::Red,
+                drawn: 14,
+                available: 12
+            }
+        );
+    }
+
+    #[test]
+    fn color_distribution_counts_every_draw() {
+        let d = color_distribution(&games(), Color::Red);
+
+        assert_eq!(d.draws(), 14);
+        assert_eq!(d.min(), Some(0));
+        assert_eq!(d.max(), Some(20));
+    }
+
+    #[test]
+    fn tightest_bag_covers_k_games() {
+        let games = games();
+
+        let bag = tightest_bag(&games, 2).unwrap();
+        assert_eq!(
+            bag,
+            Set {
+                red: 4,
+                green: 3,
+                blue: 6
+            }
+        );
+        assert!(games.iter().filter(|g| g.is_possible(&bag)).count() >= 2);
+
+        assert_eq!(tightest_bag(&games, 6), None);
+    }
+
+    #[test]
+    fn minimum_bags_are_per_game_maxima() {
+        let bags = minimum_bags(&games());
+
+        let ids: Vec<i64> = bags.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+        assert_eq!(
+            bags[0].1,
+            Set {
+                red: 4,
+                green: 2,
+                blue: 6
+            }
+        );
+        assert_eq!(
+            bags[2].1,
+            Set {
+                red: 20,
+                green: 13,
+                blue: 6
+            }
+        );
+        assert_eq!(bags.iter().map(|(_, s)| s.power()).sum::<i64>(), 2286);
+    }
+
+    #[test]
+    fn report_uses_bag_and_k_arguments() {
+        let games = games();
+
+        let default = report(&games, &[]).unwrap();
+        assert!(default.contains("impossible games for bag 12 red, 13 green, 14 blue:"));
+        assert!(default.contains("  game 3: draw 1 shows 20 Red (bag has 12)"));
+        assert!(default.contains("tightest bag for 2 games: 4 red, 3 green, 6 blue"));
+
+        let custom = report(&games, &["20", "13", "15", "6"]).unwrap();
+        assert!(custom.contains("impossible games for bag 20 red, 13 green, 15 blue:"));
+        assert!(!custom.contains("  game 3: draw"));
+        assert!(custom.contains("no bag allows 6 games"));
+    }
+
+    #[test]
+    fn report_rejects_malformed_arguments() {
+        let games = games();
+
+        assert_eq!(report(&games, &["12"]), Err(()));
+        assert_eq!(report(&games, &["12", "13"]), Err(()));
+        assert_eq!(report(&games, &["12", "13", "14", "2", "5"]), Err(()));
+        assert_eq!(report(&games, &["12", "13", "14", "-1"]), Err(()));
+        assert_eq!(report(&games, &["12", "x", "14"]), Err(()));
+    }
+}
@@ -1,90 +1,79 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::utils::grid::{Grid, Neighborhood, Point};
+
 use super::Solver;
 
+use rules::SchematicRules;
+
+pub mod export;
+pub mod rules;
+pub mod streaming;
+
 struct Symbol {
     symbol: char,
+    pos: Point,
     parts: HashSet<usize>,
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Default, Debug)]
-struct Coordinate {
-    x: u64,
-    y: u64,
-}
-
 #[derive(Clone, Default)]
 struct Part {
-    val: u64,
+    val: i64,
+    start: Point,
+    len: usize,
     symbols: HashSet<usize>,
 }
 
 pub struct Schematic {
+    grid: Grid<char>,
     parts: Vec<Part>,
     symbols: Vec<Symbol>,
+    rules: SchematicRules,
 }
 
-impl<'a> Schematic {
-    fn parse_from_strs(s: &'a [&'a str]) -> Schematic {
-        // find valid locations
-        let (valid_locations, symbols) = Self::find_valid_locations(s);
-        let (parts, symbols) = Self::find_parts(s, &valid_locations, symbols);
+impl Schematic {
+    pub fn parse_from_strs(s: &[&str]) -> Schematic {
+        Self::parse_with_rules(s, SchematicRules::default())
+    }
+
+    pub fn parse_with_rules(s: &[&str], rules: SchematicRules) -> Schematic {
+        let grid = Grid::from_lines(s);
 
-        Schematic { parts, symbols }
+        // find valid locations
+        let (valid_locations, symbols) = Self::find_valid_locations(&grid, &rules);
+        let (parts, symbols) = Self::find_parts(&grid, &valid_locations, symbols, &rules);
+
+        Schematic {
+            grid,
+            parts,
+            symbols,
+            rules,
+        }
     }
 
-    fn find_valid_locations(s: &'a [&'a str]) -> (HashMap<Coordinate, Vec<usize>>, Vec<Symbol>) {
-        let mut valid_locations: HashMap<Coordinate, Vec<usize>> = HashMap::new();
+    fn find_valid_locations(
+        grid: &Grid<char>,
+        rules: &SchematicRules,
+    ) -> (HashMap<Point, Vec<usize>>, Vec<Symbol>) {
+        let mut valid_locations: HashMap<Point, Vec<usize>> = HashMap::new();
         let mut symbols = Vec::new();
-        for (y, row_val) in s.iter().enumerate() {
-            for (x, c) in row_val.char_indices() {
-                if c.is_numeric() || c == '.' {
-                    continue;
-                }
+        for (p, &c) in grid.iter() {
+            if !rules.is_symbol(c) || rules.is_negative_sign(grid.row(p.y).unwrap(), p.x) {
+                continue;
+            }
 
-                symbols.push(Symbol {
-                    symbol: c,
-                    parts: HashSet::new(),
-                });
-                let dirs = [
-                    (-1, -1),
-                    (0, -1),
-                    (1, -1),
-                    (-1, 0),
-                    (1, 0),
-                    (-1, 1),
-                    (0, 1),
-                    (1, 1),
-                ];
-
-                // must be a symbol
-                let valid_coordinates: Vec<Coordinate> = dirs
-                    .iter()
-                    .filter_map(|&(dx, dy)| {
-                        let new_x = x as isize + dx;
-                        let new_y = y as isize + dy;
-
-                        if new_x >= 0
-                            && new_x < s[y].len() as isize
-                            && new_y >= 0
-                            && new_y < s.len() as isize
-                        {
-                            Some(Coordinate {
-                                x: new_x as u64,
-                                y: new_y as u64,
-                            })
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-
-                for coord in valid_coordinates {
-                    valid_locations
-                        .entry(coord)
-                        .or_insert_with(Vec::new)
-                        .push(symbols.len() - 1);
-                }
+            // must be a symbol
+            symbols.push(Symbol {
+                symbol: c,
+                pos: p,
+                parts: HashSet::new(),
+            });
+
+            for coord in grid.neighbors(p, Neighborhood::Eight) {
+                valid_locations
+                    .entry(coord)
+                    .or_default()
+                    .push(symbols.len() - 1);
             }
         }
 
@@ -92,27 +81,42 @@ impl<'a> Schematic {
     }
 
     fn find_parts(
-        s: &'a [&'a str],
-        valid_locations: &HashMap<Coordinate, Vec<usize>>,
+        grid: &Grid<char>,
+        valid_locations: &HashMap<Point, Vec<usize>>,
         mut symbols: Vec<Symbol>,
+        rules: &SchematicRules,
     ) -> (Vec<Part>, Vec<Symbol>) {
         let mut parts: Vec<Part> = vec![];
         let mut is_part = false;
+        let mut start = Point::default();
         let mut part_symbols: HashSet<usize> = HashSet::new();
         let mut val_string: String = String::default();
 
-        // find parts
-        for (y, val) in s.iter().enumerate() {
-            for (x, c) in val.char_indices() {
-                let x = x as u64;
-                let y = y as u64;
+        // find parts, ending any number that runs up to the end of a row
+        for (y, row) in grid.rows().enumerate() {
+            for (x, &c) in row.iter().chain(std::iter::once(&'.')).enumerate() {
+                let is_sign = rules.is_negative_sign(row, x);
 
-                if c.is_numeric() {
+                if is_part && (is_sign || !c.is_ascii_digit()) {
+                    parts.push(Part {
+                        val: val_string.parse().unwrap(),
+                        start,
+                        len: val_string.len(),
+                        symbols: part_symbols.clone().into_iter().collect(),
+                    });
+
+                    is_part = false;
+                    part_symbols.clear();
+                    val_string.clear();
+                }
+
+                if c.is_ascii_digit() || is_sign {
                     if !is_part {
                         is_part = true;
+                        start = Point { x, y };
                     }
 
-                    if let Some(symbol_indices) = valid_locations.get(&Coordinate { x, y }) {
+                    if let Some(symbol_indices) = valid_locations.get(&Point { x, y }) {
                         part_symbols.extend(symbol_indices);
                         for symbol_idx in symbol_indices {
                             symbols
@@ -123,35 +127,20 @@ impl<'a> Schematic {
                         }
                     }
                     val_string.push(c);
-                } else {
-                    if is_part {
-                        parts.push(Part {
-                            val: val_string.parse().unwrap(),
-                            symbols: part_symbols.clone().into_iter().collect(),
-                        });
-                    }
-
-                    is_part = false;
-                    part_symbols.clear();
-                    val_string.clear();
                 }
             }
         }
 
-        if is_part {
-            parts.push(Part {
-                val: val_string.parse().unwrap(),
-                symbols: part_symbols.into_iter().collect(),
-            });
-        }
-
         (parts, symbols)
     }
 
-    fn get_gear_ratio(&self, symbol: &Symbol) -> Option<u64> {
-        if symbol.symbol == '*' && symbol.parts.len() == 2 {
-            let parts: Vec<&usize> = symbol.parts.iter().collect();
-            Some(self.parts.get(*parts[0]).unwrap().val * self.parts.get(*parts[1]).unwrap().val)
+    fn get_gear_ratio(&self, symbol: &Symbol) -> Option<i64> {
+        if symbol.symbol == self.rules.gear && symbol.parts.len() == self.rules.gear_parts {
+            Some(
+                self.rules
+                    .combine
+                    .apply(symbol.parts.iter().map(|&i| self.parts[i].val)),
+            )
         } else {
             None
         }
@@ -163,7 +152,7 @@ pub struct DayThreeSolver {}
 impl Solver<'_> for DayThreeSolver {
     fn part_1<'a>(&self, input: &'a [&'a str]) -> Result<String, ()> {
         let schematic = Schematic::parse_from_strs(input);
-        let sum: u64 = schematic
+        let sum: i64 = schematic
             .parts
             .iter()
             .filter(|p| !p.symbols.is_empty())
@@ -176,7 +165,7 @@ impl Solver<'_> for DayThreeSolver {
     fn part_2<'a>(&self, input: &'a [&'a str]) -> Result<String, ()> {
         let schematic = Schematic::parse_from_strs(input);
 
-        let sum: u64 = schematic
+        let sum: i64 = schematic
             .symbols
             .iter()
             .filter_map(|s| schematic.get_gear_ratio(s))
@@ -184,13 +173,27 @@ impl Solver<'_> for DayThreeSolver {
 
         Ok(sum.to_string())
     }
+
+    fn run_mode<'a>(&self, mode: &str, _args: &[&str], input: &'a [&'a str]) -> Result<String, ()> {
+        let schematic = Schematic::parse_from_strs(input);
+
+        match mode {
+            "render" => Ok(schematic.render_ansi()),
+            "json" => Ok(schematic.to_json()),
+            "dot" => Ok(schematic.to_dot()),
+            _ => Err(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::solutions::{normalize_input, Solver};
 
-    use super::DayThreeSolver;
+    use super::{
+        rules::{Combine, SchematicRules},
+        DayThreeSolver, Schematic,
+    };
 
     const INPUT: &str = "
     467..114..
@@ -222,4 +225,78 @@ mod tests {
 
         assert_eq!(res, 467835);
     }
+
+    #[test]
+    fn numbers_end_at_row_boundaries() {
+        let input = normalize_input(
+            "
+            ..12
+            34*.
+            ",
+        )
+        .unwrap();
+        let solver = DayThreeSolver {};
+        let res: i64 = solver.part_1(&input).unwrap().parse().unwrap();
+
+        assert_eq!(res, 46);
+    }
+
+    fn sums(schematic: &Schematic) -> (i64, i64) {
+        let parts = schematic
+            .parts
+            .iter()
+            .filter(|p| !p.symbols.is_empty())
+            .map(|p| p.val)
+            .sum();
+        let gears = schematic
+            .symbols
+            .iter()
+            .filter_map(|s| schematic.get_gear_ratio(s))
+            .sum();
+
+        (parts, gears)
+    }
+
+    #[test]
+    fn configurable_symbols_and_gears() {
+        let input = normalize_input(INPUT).unwrap();
+
+        let summed = Schematic::parse_with_rules(
+            &input,
+            SchematicRules {
+                combine: Combine::Sum,
+                ..Default::default()
+            },
+        );
+        assert_eq!(sums(&summed), (4361, 467 + 35 + 755 + 598));
+
+        let stars_only = Schematic::parse_with_rules(
+            &input,
+            SchematicRules {
+                symbols: Some(vec!['*']),
+                gear_parts: 1,
+                ..Default::default()
+            },
+        );
+        assert_eq!(sums(&stars_only), (467 + 35 + 617 + 755 + 598, 617));
+    }
+
+    #[test]
+    fn negative_numbers() {
+        let input = ["-12*", "..-3"];
+
+        let signed = Schematic::parse_with_rules(
+            &input,
+            SchematicRules {
+                negative_numbers: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(sums(&signed), (-15, 36));
+        assert_eq!(signed.symbols.len(), 1);
+
+        let unsigned = Schematic::parse_from_strs(&input);
+        assert_eq!(sums(&unsigned), (15, 36));
+        assert_eq!(unsigned.symbols.len(), 3);
+    }
 }
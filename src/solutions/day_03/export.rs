@@ -0,0 +1,240 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use crate::utils::grid::Point;
+
+use super::Schematic;
+
+const ATTACHED: &str = "\x1b[32m";
+const UNATTACHED: &str = "\x1b[31m";
+const GEAR: &str = "\x1b[1;33m";
+const SYMBOL: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+// hash set iteration order isn't stable, so exports always list indices in order
+fn sorted(set: &HashSet<usize>) -> Vec<usize> {
+    let mut v: Vec<usize> = set.iter().copied().collect();
+    v.sort();
+    v
+}
+
+fn json_list(set: &HashSet<usize>) -> String {
+    let items: Vec<String> = sorted(set).iter().map(|i| i.to_string()).collect();
+    format!("[{}]", items.join(","))
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '"' => "\\\"".to_string(),
+        '\\' => "\\\\".to_string(),
+        '\n' => "\\n".to_string(),
+        '\r' => "\\r".to_string(),
+        '\t' => "\\t".to_string(),
+        c if (c as u32) < 0x20 => format!("\\u{:04x}", c as u32),
+        _ => c.to_string(),
+    }
+}
+
+impl Schematic {
+    /// Draws the schematic with part numbers touching a symbol in green, stray numbers in red
+    /// and gears in yellow, listing each row's gear ratios at the end of the row.
+    pub fn render_ansi(&self) -> String {
+        let mut part_at: HashMap<Point, usize> = HashMap::new();
+        for (i, part) in self.parts.iter().enumerate() {
+            for dx in 0..part.len {
+                part_at.insert(Point::new(part.start.x + dx, part.start.y), i);
+            }
+        }
+        let symbol_at: HashMap<Point, usize> = self
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.pos, i))
+            .collect();
+
+        let mut out = String::new();
+        for (y, row) in self.grid.rows().enumerate() {
+            let mut ratios = vec![];
+            for (x, c) in row.iter().enumerate() {
+                let p = Point::new(x, y);
+                let color = if let Some(&i) = part_at.get(&p) {
+                    if self.parts[i].symbols.is_empty() {
+                        UNATTACHED
+                    } else {
+                        ATTACHED
+                    }
+                } else if let Some(&i) = symbol_at.get(&p) {
+                    match self.get_gear_ratio(&self.symbols[i]) {
+                        Some(ratio) => {
+                            ratios.push(ratio);
+                            GEAR
+                        }
+                        None => SYMBOL,
+                    }
+                } else {
+                    out.push(*c);
+                    continue;
+                };
+
+                // writing to a String can't fail
+                write!(out, "{}{}{}", color, c, RESET).unwrap();
+            }
+
+            for ratio in ratios {
+                write!(out, "  {}{}{}", GEAR, ratio, RESET).unwrap();
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"parts\":[");
+        for (i, part) in self.parts.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write!(
+                out,
+                "{{\"id\":{},\"value\":{},\"x\":{},\"y\":{},\"len\":{},\"symbols\":{}}}",
+                i,
+                part.val,
+                part.start.x,
+                part.start.y,
+                part.len,
+                json_list(&part.symbols)
+            )
+            .unwrap();
+        }
+
+        out.push_str("],\"symbols\":[");
+        for (i, symbol) in self.symbols.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let gear_ratio = self
+                .get_gear_ratio(symbol)
+                .map_or("null".to_string(), |r| r.to_string());
+            write!(
+                out,
+                "{{\"id\":{},\"symbol\":\"{}\",\"x\":{},\"y\":{},\"parts\":{},\"gear_ratio\":{}}}",
+                i,
+                escape(symbol.symbol),
+                symbol.pos.x,
+                symbol.pos.y,
+                json_list(&symbol.parts),
+                gear_ratio
+            )
+            .unwrap();
+        }
+        out.push_str("]}");
+
+        out
+    }
+
+    /// Parts are boxes (dashed when unattached), symbols are circles (filled when a gear),
+    /// and an edge joins every adjacent part and symbol.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("graph schematic {\n");
+        for (i, part) in self.parts.iter().enumerate() {
+            let style = if part.symbols.is_empty() {
+                ", style=dashed"
+            } else {
+                ""
+            };
+            writeln!(
+                out,
+                "  p{} [label=\"{}\", shape=box{}];",
+                i, part.val, style
+            )
+            .unwrap();
+        }
+
+        for (i, symbol) in self.symbols.iter().enumerate() {
+            match self.get_gear_ratio(symbol) {
+                Some(ratio) => writeln!(
+                    out,
+                    "  s{} [label=\"{} ({})\", shape=circle, style=filled];",
+                    i,
+                    escape(symbol.symbol),
+                    ratio
+                ),
+                None => writeln!(
+                    out,
+                    "  s{} [label=\"{}\", shape=circle];",
+                    i,
+                    escape(symbol.symbol)
+                ),
+            }
+            .unwrap();
+        }
+
+        for (i, part) in self.parts.iter().enumerate() {
+            for s in sorted(&part.symbols) {
+                writeln!(out, "  p{} -- s{};", i, s).unwrap();
+            }
+        }
+        out.push_str("}\n");
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solutions::day_03::Schematic;
+
+    const INPUT: [&str; 3] = ["467.1", "..*..", "35..\""];
+
+    #[test]
+    fn exports_json() {
+        let schematic = Schematic::parse_from_strs(&INPUT);
+
+        assert_eq!(
+            schematic.to_json(),
+            "{\"parts\":[\
+             {\"id\":0,\"value\":467,\"x\":0,\"y\":0,\"len\":3,\"symbols\":[0]},\
+             {\"id\":1,\"value\":1,\"x\":4,\"y\":0,\"len\":1,\"symbols\":[]},\
+             {\"id\":2,\"value\":35,\"x\":0,\"y\":2,\"len\":2,\"symbols\":[0]}],\
+             \"symbols\":[\
+             {\"id\":0,\"symbol\":\"*\",\"x\":2,\"y\":1,\"parts\":[0,2],\"gear_ratio\":16345},\
+             {\"id\":1,\"symbol\":\"\\\"\",\"x\":4,\"y\":2,\"parts\":[],\"gear_ratio\":null}]}"
+        );
+    }
+
+    #[test]
+    fn exports_dot() {
+        let schematic = Schematic::parse_from_strs(&INPUT);
+        let dot = schematic.to_dot();
+
+        assert!(dot.contains("  p1 [label=\"1\", shape=box, style=dashed];\n"));
+        assert!(dot.contains("  s0 [label=\"* (16345)\", shape=circle, style=filled];\n"));
+        assert!(dot.contains("  p0 -- s0;\n  p2 -- s0;\n"));
+    }
+
+    #[test]
+    fn renders_gear_ratios() {
+        let schematic = Schematic::parse_from_strs(&INPUT);
+        let lines: Vec<String> = schematic.render_ansi().lines().map(String::from).collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("\x1b[32m4\x1b[0m"));
+        assert!(lines[0].ends_with("\x1b[31m1\x1b[0m"));
+        assert!(lines[1].ends_with("  \x1b[1;33m16345\x1b[0m"));
+    }
+
+    #[test]
+    fn escapes_control_characters() {
+        assert_eq!(super::escape('\t'), "\\t");
+        assert_eq!(super::escape('\n'), "\\n");
+        assert_eq!(super::escape('\r'), "\\r");
+
+        let schematic = Schematic::parse_from_strs(&["1\x01"]);
+        assert!(schematic
+            .to_json()
+            .contains("{\"id\":0,\"symbol\":\"\\u0001\",\"x\":1,\"y\":0,"));
+    }
+}
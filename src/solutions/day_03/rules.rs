@@ -0,0 +1,63 @@
+/// How the parts around a gear combine into its ratio.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Combine {
+    #[default]
+    Product,
+    Sum,
+}
+
+impl Combine {
+    pub fn apply(&self, vals: impl Iterator<Item = i64>) -> i64 {
+        match self {
+            Combine::Product => vals.product(),
+            Combine::Sum => vals.sum(),
+        }
+    }
+}
+
+/// Controls how a schematic is read. The default matches the puzzle: `.` is empty, every other
+/// non-digit is a symbol, and a `*` touching exactly two parts is a gear whose ratio is their
+/// product.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SchematicRules {
+    pub empty: Vec<char>,
+    /// if set, only these characters are symbols and anything else is treated as empty
+    pub symbols: Option<Vec<char>>,
+    pub gear: char,
+    pub gear_parts: usize,
+    pub combine: Combine,
+    /// reads a `-` directly before a digit as the sign of that number rather than a symbol
+    pub negative_numbers: bool,
+}
+
+impl Default for SchematicRules {
+    fn default() -> Self {
+        SchematicRules {
+            empty: vec!['.'],
+            symbols: None,
+            gear: '*',
+            gear_parts: 2,
+            combine: Combine::Product,
+            negative_numbers: false,
+        }
+    }
+}
+
+impl SchematicRules {
+    pub fn is_symbol(&self, c: char) -> bool {
+        if c.is_ascii_digit() || self.empty.contains(&c) {
+            return false;
+        }
+
+        match &self.symbols {
+            Some(symbols) => symbols.contains(&c),
+            None => true,
+        }
+    }
+
+    pub fn is_negative_sign(&self, row: &[char], x: usize) -> bool {
+        self.negative_numbers
+            && row.get(x) == Some(&'-')
+            && row.get(x + 1).is_some_and(|c| c.is_ascii_digit())
+    }
+}
@@ -0,0 +1,248 @@
+use std::io::{self, BufRead};
+
+use super::rules::SchematicRules;
+
+/// Both puzzle answers for a schematic.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct StreamTotals {
+    pub part_sum: i64,
+    pub gear_ratio_sum: i64,
+}
+
+struct Number {
+    start: usize,
+    end: usize,
+    val: i64,
+}
+
+impl Number {
+    fn touches(&self, x: usize) -> bool {
+        self.start <= x + 1 && x <= self.end + 1
+    }
+}
+
+struct Row {
+    numbers: Vec<Number>,
+    symbols: Vec<(usize, char)>,
+}
+
+impl Row {
+    fn parse(line: &str, rules: &SchematicRules) -> Row {
+        let chars: Vec<char> = line.chars().collect();
+        let mut numbers = vec![];
+        let mut symbols = vec![];
+
+        let mut x = 0;
+        while x < chars.len() {
+            let is_sign = rules.is_negative_sign(&chars, x);
+            if chars[x].is_ascii_digit() || is_sign {
+                let start = x;
+                x += 1;
+                while x < chars.len() && chars[x].is_ascii_digit() {
+                    x += 1;
+                }
+
+                let val: String = chars[start..x].iter().collect();
+                numbers.push(Number {
+                    start,
+                    end: x - 1,
+                    val: val.parse().unwrap(),
+                });
+                continue;
+            }
+
+            if rules.is_symbol(chars[x]) {
+                symbols.push((x, chars[x]));
+            }
+            x += 1;
+        }
+
+        Row { numbers, symbols }
+    }
+}
+
+/// Computes both answers in a single pass, holding only three rows at a time. A row's part
+/// numbers and gears can be settled as soon as the row below it has been read.
+pub fn stream_totals<I, S>(lines: I, rules: &SchematicRules) -> StreamTotals
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut totals = StreamTotals::default();
+    let mut window: [Option<Row>; 3] = [None, None, None];
+
+    let mut rows = lines
+        .into_iter()
+        .fuse()
+        .map(|line| Some(Row::parse(line.as_ref(), rules)));
+
+    // one extra step past the end so the last row gets its turn in the middle
+    let mut remaining = 2;
+    while remaining > 0 {
+        let next = match rows.next() {
+            Some(row) => row,
+            None => {
+                remaining -= 1;
+                None
+            }
+        };
+        window.rotate_left(1);
+        window[2] = next;
+
+        if let Some(row) = &window[1] {
+            settle_row(row, &window, rules, &mut totals);
+        }
+    }
+
+    totals
+}
+
+/// Like `stream_totals`, reading trimmed, non-empty lines from `reader`.
+pub fn stream_totals_from_reader(
+    reader: impl BufRead,
+    rules: &SchematicRules,
+) -> io::Result<StreamTotals> {
+    let mut err = None;
+    let lines = reader
+        .lines()
+        .map_while(|line| line.map_err(|e| err = Some(e)).ok())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.trim().to_string());
+
+    let totals = stream_totals(lines, rules);
+    match err {
+        Some(e) => Err(e),
+        None => Ok(totals),
+    }
+}
+
+fn settle_row(
+    row: &Row,
+    window: &[Option<Row>; 3],
+    rules: &SchematicRules,
+    totals: &mut StreamTotals,
+) {
+    let neighbors = || window.iter().flatten();
+
+    for number in &row.numbers {
+        let attached = neighbors()
+            .flat_map(|r| &r.symbols)
+            .any(|&(x, _)| number.touches(x));
+        if attached {
+            totals.part_sum += number.val;
+        }
+    }
+
+    for &(x, c) in &row.symbols {
+        if c != rules.gear {
+            continue;
+        }
+
+        let parts: Vec<i64> = neighbors()
+            .flat_map(|r| &r.numbers)
+            .filter(|n| n.touches(x))
+            .map(|n| n.val)
+            .collect();
+        if parts.len() == rules.gear_parts {
+            totals.gear_ratio_sum += rules.combine.apply(parts.into_iter());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solutions::{
+        day_03::{
+            rules::{Combine, SchematicRules},
+            Schematic,
+        },
+        normalize_input,
+    };
+
+    use super::*;
+
+    const INPUT: &str = "
+    467..114..
+    ...*......
+    ..35..633.
+    ......#...
+    617*......
+    .....+.58.
+    ..592.....
+    ......755.
+    ...$.*....
+    .664.598..
+    ";
+
+    fn schematic_totals(lines: &[&str], rules: SchematicRules) -> StreamTotals {
+        let schematic = Schematic::parse_with_rules(lines, rules);
+        StreamTotals {
+            part_sum: schematic
+                .parts
+                .iter()
+                .filter(|p| !p.symbols.is_empty())
+                .map(|p| p.val)
+                .sum(),
+            gear_ratio_sum: schematic
+                .symbols
+                .iter()
+                .filter_map(|s| schematic.get_gear_ratio(s))
+                .sum(),
+        }
+    }
+
+    #[test]
+    fn matches_schematic() {
+        let input = normalize_input(INPUT).unwrap();
+        let variants = [
+            SchematicRules::default(),
+            SchematicRules {
+                combine: Combine::Sum,
+                gear_parts: 1,
+                ..Default::default()
+            },
+        ];
+
+        for rules in variants {
+            assert_eq!(
+                stream_totals(&input, &rules),
+                schematic_totals(&input, rules.clone())
+            );
+        }
+
+        let signed = SchematicRules {
+            negative_numbers: true,
+            ..Default::default()
+        };
+        let input = ["-12*", "..-3", "1", "*-4."];
+        assert_eq!(
+            stream_totals(input, &signed),
+            schematic_totals(&input, signed.clone())
+        );
+    }
+
+    #[test]
+    fn reads_from_a_reader() {
+        let totals =
+            stream_totals_from_reader(INPUT.as_bytes(), &SchematicRules::default()).unwrap();
+
+        assert_eq!(
+            totals,
+            StreamTotals {
+                part_sum: 4361,
+                gear_ratio_sum: 467835
+            }
+        );
+    }
+
+    #[test]
+    fn short_inputs() {
+        let rules = SchematicRules::default();
+
+        assert_eq!(
+            stream_totals(Vec::<&str>::new(), &rules),
+            StreamTotals::default()
+        );
+        assert_eq!(stream_totals(["1*2"], &rules).gear_ratio_sum, 2);
+    }
+}
@@ -1,90 +1,229 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, fmt::Display, str::FromStr};
+
+use crate::utils::big_uint::BigUint;
 
 use super::Solver;
 
-fn parse_line(s: &str) -> (Vec<u64>, Vec<u64>) {
-    let mut split = s.split(" | ");
+pub mod trace;
 
-    let (winning_nums_raw, scratch_nums_raw) = (split.next().unwrap(), split.next().unwrap());
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ScratchcardError {
+    MissingColon,
+    InvalidId(String),
+    MissingSeparator,
+    InvalidNumber(String),
+    DuplicateWinning(u64),
+    DuplicateHeld(u64),
+    UnexpectedId {
+        line: usize,
+        expected: u64,
+        found: u64,
+    },
+    Overflow {
+        card: u64,
+    },
+}
 
-    let winning_nums_raw = winning_nums_raw.split(':').last().unwrap();
+impl Display for ScratchcardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScratchcardError::MissingColon => write!(f, "expected ':' after the card id"),
+            ScratchcardError::InvalidId(s) => write!(f, "invalid card id '{}'", s),
+            ScratchcardError::MissingSeparator => write!(f, "expected '|' between number lists"),
+            ScratchcardError::InvalidNumber(s) => write!(f, "invalid number '{}'", s),
+            ScratchcardError::DuplicateWinning(n) => write!(f, "winning number {} repeated", n),
+            ScratchcardError::DuplicateHeld(n) => write!(f, "held number {} repeated", n),
+            ScratchcardError::UnexpectedId {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected card {}, found {}",
+                line, expected, found
+            ),
+            ScratchcardError::Overflow { card } => {
+                write!(f, "card {}: result doesn't fit in a u64", card)
+            }
+        }
+    }
+}
 
-    let winning_nums = winning_nums_raw
-        .split_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect::<Vec<u64>>();
+impl std::error::Error for ScratchcardError {}
 
-    let scratch_nums: Vec<u64> = scratch_nums_raw
-        .split_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect::<Vec<u64>>();
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Scratchcard {
+    pub id: u64,
+    pub winning: Vec<u64>,
+    pub held: Vec<u64>,
+    matches: u32,
+}
+
+impl Scratchcard {
+    pub fn new(id: u64, winning: Vec<u64>, held: Vec<u64>) -> Result<Self, ScratchcardError> {
+        let winning_set = unique(&winning).map_err(ScratchcardError::DuplicateWinning)?;
+        let held_set = unique(&held).map_err(ScratchcardError::DuplicateHeld)?;
+        let matches = winning_set.intersection(&held_set).count() as u32;
 
-    (winning_nums, scratch_nums)
+        Ok(Scratchcard {
+            id,
+            winning,
+            held,
+            matches,
+        })
+    }
+
+    /// How many held numbers are also winning numbers.
+    pub fn matches(&self) -> u32 {
+        self.matches
+    }
+
+    pub fn score(&self) -> Result<u64, ScratchcardError> {
+        if self.matches == 0 {
+            Ok(0)
+        } else {
+            2_u64
+                .checked_pow(self.matches - 1)
+                .ok_or(ScratchcardError::Overflow { card: self.id })
+        }
+    }
 }
 
-fn num_matches<'a>(winning_nums: &'a [u64], scratch_nums: &'a [u64]) -> u32 {
-    let winning_nums = winning_nums.iter().cloned().collect::<HashSet<u64>>();
-    let scratch_nums = scratch_nums.iter().cloned().collect::<HashSet<u64>>();
+fn unique(nums: &[u64]) -> Result<HashSet<u64>, u64> {
+    let mut set = HashSet::new();
+    for &n in nums {
+        if !set.insert(n) {
+            return Err(n);
+        }
+    }
+
+    Ok(set)
+}
 
-    winning_nums.intersection(&scratch_nums).cloned().count() as u32
+fn parse_nums(s: &str) -> Result<Vec<u64>, ScratchcardError> {
+    s.split_whitespace()
+        .map(|n| {
+            n.parse()
+                .map_err(|_| ScratchcardError::InvalidNumber(n.to_string()))
+        })
+        .collect()
 }
 
-fn calculate_score<'a>(winning_nums: &'a [u64], scratch_nums: &'a [u64]) -> u64 {
-    let matching_numbers_count = num_matches(winning_nums, scratch_nums);
-    if matching_numbers_count == 0 {
-        0
-    } else {
-        2_u64.pow(matching_numbers_count - 1)
+impl FromStr for Scratchcard {
+    type Err = ScratchcardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (header, nums) = s.split_once(':').ok_or(ScratchcardError::MissingColon)?;
+
+        let id = header.trim();
+        let id = id
+            .strip_prefix("Card")
+            .map(|id| id.trim())
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| ScratchcardError::InvalidId(id.to_string()))?;
+
+        let (winning, held) = nums
+            .split_once('|')
+            .ok_or(ScratchcardError::MissingSeparator)?;
+
+        Scratchcard::new(id, parse_nums(winning)?, parse_nums(held)?)
     }
 }
 
-pub struct DayFourSolver {}
+/// Parses every card, checking the ids run 1, 2, 3... in order since copies are won by position.
+pub fn parse_cards(lines: &[&str]) -> Result<Vec<Scratchcard>, ScratchcardError> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let card: Scratchcard = line.parse()?;
+            let expected = i as u64 + 1;
+            if card.id != expected {
+                return Err(ScratchcardError::UnexpectedId {
+                    line: i + 1,
+                    expected,
+                    found: card.id,
+                });
+            }
 
-impl<'a> Solver<'a> for DayFourSolver {
-    fn part_1(&self, input: &'a [&'a str]) -> Result<String, ()> {
-        let games: u64 = input
-            .iter()
-            .map(|line| {
-                let (winning_nums, scratch_nums) = parse_line(line);
+            Ok(card)
+        })
+        .collect()
+}
 
-                calculate_score(&winning_nums, &scratch_nums)
-            })
-            .sum();
+pub fn total_score(cards: &[Scratchcard]) -> Result<u64, ScratchcardError> {
+    cards.iter().try_fold(0_u64, |acc, card| {
+        acc.checked_add(card.score()?)
+            .ok_or(ScratchcardError::Overflow { card: card.id })
+    })
+}
 
-        Ok(games.to_string())
+/// How many instances of each card we end up with, counting the original. Fails if any count
+/// gets too big for a `u64`; see `count_copies_big` for those inputs.
+pub fn count_copies(cards: &[Scratchcard]) -> Result<Vec<u64>, ScratchcardError> {
+    let mut num_of_instances = vec![1_u64; cards.len()];
+
+    for (i, card) in cards.iter().enumerate() {
+        // win all cards from the next one to the number of matches
+        for j in i + 1..cards.len().min(i + card.matches() as usize + 1) {
+            num_of_instances[j] = num_of_instances[j]
+                .checked_add(num_of_instances[i])
+                .ok_or(ScratchcardError::Overflow { card: cards[j].id })?;
+        }
     }
 
-    fn part_2(&self, input: &'a [&'a str]) -> Result<String, ()> {
-        let mut num_of_instances = vec![1_u32; input.len()];
+    Ok(num_of_instances)
+}
 
-        let all_matches = input
-            .iter()
-            .map(|line| {
-                let (winning_nums, scratch_nums) = parse_line(line);
+pub fn count_copies_big(cards: &[Scratchcard]) -> Vec<BigUint> {
+    let mut num_of_instances = vec![BigUint::from(1); cards.len()];
 
-                num_matches(&winning_nums, &scratch_nums)
-            })
-            .collect::<Vec<u32>>();
+    for (i, card) in cards.iter().enumerate() {
+        let won = num_of_instances[i].clone();
+        for count in num_of_instances
+            .iter_mut()
+            .take(i + card.matches() as usize + 1)
+            .skip(i + 1)
+        {
+            *count += &won;
+        }
+    }
 
-        println!("all matches: {:?}", all_matches);
+    num_of_instances
+}
 
-        for (i, &matches) in all_matches.iter().enumerate() {
-            if i == all_matches.len() - 1 {
-                break;
-            }
+pub struct DayFourSolver {}
 
-            if matches == 0 {
-                continue;
-            }
+impl<'a> Solver<'a> for DayFourSolver {
+    fn part_1(&self, input: &'a [&'a str]) -> Result<String, ()> {
+        let cards = parse_cards(input).map_err(|_| ())?;
+        let games = total_score(&cards).map_err(|_| ())?;
 
-            // win all cards from the next one to the number of matches
-            for j in i + 1..all_matches.len().min(i + matches as usize + 1) {
-                num_of_instances[j] += num_of_instances[i];
-            }
+        Ok(games.to_string())
+    }
+
+    fn part_2(&self, input: &'a [&'a str]) -> Result<String, ()> {
+        let cards = parse_cards(input).map_err(|_| ())?;
+
+        // only switch to arbitrary precision when the counts actually get that large
+        let total_instances = count_copies(&cards)
+            .ok()
+            .and_then(|counts| counts.iter().try_fold(0_u64, |acc, &n| acc.checked_add(n)));
+        match total_instances {
+            Some(total) => Ok(total.to_string()),
+            None => Ok(count_copies_big(&cards).iter().sum::<BigUint>().to_string()),
         }
+    }
 
-        let total_instances: u32 = num_of_instances.iter().sum();
-        Ok(total_instances.to_string())
+    fn run_mode(&self, mode: &str, _args: &[&str], input: &'a [&'a str]) -> Result<String, ()> {
+        let cards = parse_cards(input).map_err(|_| ())?;
+        let traces = trace::trace_copies(&cards);
+
+        match mode {
+            "trace" => Ok(trace::trace_table(&traces)),
+            "dot" => Ok(trace::trace_dot(&traces)),
+            _ => Err(()),
+        }
     }
 }
 
@@ -92,7 +231,10 @@ impl<'a> Solver<'a> for DayFourSolver {
 mod tests {
     use crate::solutions::{normalize_input, Solver};
 
-    use super::DayFourSolver;
+    use super::{
+        count_copies, count_copies_big, parse_cards, total_score, DayFourSolver, Scratchcard,
+        ScratchcardError,
+    };
 
     const INPUT: &str = "
         Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
@@ -122,4 +264,99 @@ mod tests {
 
         assert_eq!(res, "30");
     }
+
+    #[test]
+    fn parses_cards() {
+        let card: Scratchcard = "Card  3:  1 21 53 | 69  1 21 14".parse().unwrap();
+
+        assert_eq!(card.id, 3);
+        assert_eq!(card.winning, vec![1, 21, 53]);
+        assert_eq!(card.held, vec![69, 1, 21, 14]);
+        assert_eq!(card.matches(), 2);
+        assert_eq!(card.score(), Ok(2));
+    }
+
+    #[test]
+    fn rejects_malformed_cards() {
+        let parse = |s: &str| s.parse::<Scratchcard>().unwrap_err();
+
+        assert_eq!(parse("Card 1: 1 2 3 4"), ScratchcardError::MissingSeparator);
+        assert_eq!(parse("Card 1 1 2 | 3"), ScratchcardError::MissingColon);
+        assert_eq!(
+            parse("Card x: 1 | 2"),
+            ScratchcardError::InvalidId("Card x".to_string())
+        );
+        assert_eq!(
+            parse("Card 1: 1 a | 2"),
+            ScratchcardError::InvalidNumber("a".to_string())
+        );
+        assert_eq!(
+            parse("Card 1: 1 2 1 | 2"),
+            ScratchcardError::DuplicateWinning(1)
+        );
+        assert_eq!(parse("Card 1: 1 | 2 2"), ScratchcardError::DuplicateHeld(2));
+    }
+
+    #[test]
+    fn cross_checks_card_ids() {
+        let err = parse_cards(&["Card 1: 1 | 1", "Card 3: 2 | 2"]).unwrap_err();
+
+        assert_eq!(
+            err,
+            ScratchcardError::UnexpectedId {
+                line: 2,
+                expected: 2,
+                found: 3
+            }
+        );
+    }
+
+    // every card wins a copy of every card after it, so the counts double each time
+    fn cascade(n: u64) -> Vec<Scratchcard> {
+        (1..=n)
+            .map(|id| {
+                let nums: Vec<u64> = (0..n - id).collect();
+                Scratchcard::new(id, nums.clone(), nums).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scores_overflow_cleanly() {
+        let nums: Vec<u64> = (0..64).collect();
+        let card = Scratchcard::new(1, nums.clone(), nums).unwrap();
+        assert_eq!(card.score(), Ok(1 << 63));
+        assert_eq!(
+            total_score(&[card.clone(), card]),
+            Err(ScratchcardError::Overflow { card: 1 })
+        );
+
+        let nums: Vec<u64> = (0..65).collect();
+        let card = Scratchcard::new(7, nums.clone(), nums).unwrap();
+        assert_eq!(card.score(), Err(ScratchcardError::Overflow { card: 7 }));
+    }
+
+    #[test]
+    fn copy_counts_fall_back_to_big_integers() {
+        let cards = cascade(70);
+
+        assert_eq!(
+            count_copies(&cards),
+            Err(ScratchcardError::Overflow { card: 65 })
+        );
+
+        let counts = count_copies_big(&cards);
+        assert_eq!(counts[69].to_string(), (1_u128 << 69).to_string());
+
+        let lines: Vec<String> = cards
+            .iter()
+            .map(|c| {
+                let nums: Vec<String> = c.winning.iter().map(|n| n.to_string()).collect();
+                format!("Card {}: {} | {}", c.id, nums.join(" "), nums.join(" "))
+            })
+            .collect();
+        let input: Vec<&str> = lines.iter().map(|s| s.as_str()).collect();
+        let res = DayFourSolver {}.part_2(&input).unwrap();
+        assert_eq!(res, ((1_u128 << 70) - 1).to_string());
+    }
 }
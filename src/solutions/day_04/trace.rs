@@ -0,0 +1,169 @@
+use std::fmt::Write;
+
+use crate::utils::big_uint::BigUint;
+
+use super::{count_copies_big, Scratchcard};
+
+/// Where a card's instances came from: the original plus one copy per instance of each
+/// earlier card whose matches reached it. Counts are arbitrary precision, so every input
+/// part 2 can answer can be traced.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CardTrace {
+    pub id: u64,
+    pub matches: u32,
+    pub instances: BigUint,
+    /// `(card id, copies won from it)`, in card order
+    pub won_from: Vec<(u64, BigUint)>,
+}
+
+pub fn trace_copies(cards: &[Scratchcard]) -> Vec<CardTrace> {
+    let counts = count_copies_big(cards);
+
+    let mut traces: Vec<CardTrace> = cards
+        .iter()
+        .zip(&counts)
+        .map(|(card, instances)| CardTrace {
+            id: card.id,
+            matches: card.matches(),
+            instances: instances.clone(),
+            won_from: vec![],
+        })
+        .collect();
+
+    for (i, card) in cards.iter().enumerate() {
+        for trace in traces
+            .iter_mut()
+            .take(i + card.matches() as usize + 1)
+            .skip(i + 1)
+        {
+            trace.won_from.push((card.id, counts[i].clone()));
+        }
+    }
+
+    traces
+}
+
+pub fn trace_table(traces: &[CardTrace]) -> String {
+    let mut out = String::from("card | matches | instances | won from\n");
+    for trace in traces {
+        let won_from = if trace.won_from.is_empty() {
+            "-".to_string()
+        } else {
+            trace
+                .won_from
+                .iter()
+                .map(|(id, copies)| format!("{} (x{})", id, copies))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        // `BigUint` ignores width flags, so pad its rendered digits instead
+        // writing to a String can't fail
+        writeln!(
+            out,
+            "{:>4} | {:>7} | {:>9} | {}",
+            trace.id,
+            trace.matches,
+            trace.instances.to_string(),
+            won_from
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+/// One node per card labelled with its instance count, and an edge from each card to every
+/// card it won copies of, labelled with how many.
+pub fn trace_dot(traces: &[CardTrace]) -> String {
+    let mut out = String::from("digraph cascade {\n");
+    for trace in traces {
+        writeln!(
+            out,
+            "  c{} [label=\"Card {}\\n{} instances\"];",
+            trace.id, trace.id, trace.instances
+        )
+        .unwrap();
+    }
+    for trace in traces {
+        for (from, copies) in &trace.won_from {
+            writeln!(out, "  c{} -> c{} [label=\"{}\"];", from, trace.id, copies).unwrap();
+        }
+    }
+    out.push_str("}\n");
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solutions::{day_04::parse_cards, normalize_input};
+
+    use super::*;
+
+    const INPUT: &str = "
+        Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+        Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+        Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+        Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+        Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+    ";
+
+    fn traces() -> Vec<CardTrace> {
+        let input = normalize_input(INPUT).unwrap();
+        trace_copies(&parse_cards(&input).unwrap())
+    }
+
+    #[test]
+    fn traces_contributions() {
+        let traces = traces();
+
+        assert_eq!(
+            traces[3],
+            CardTrace {
+                id: 4,
+                matches: 1,
+                instances: BigUint::from(8),
+                won_from: vec![
+                    (1, BigUint::from(1)),
+                    (2, BigUint::from(2)),
+                    (3, BigUint::from(4))
+                ],
+            }
+        );
+        assert_eq!(traces[5].won_from, vec![]);
+        for trace in &traces {
+            let won: BigUint = trace.won_from.iter().map(|(_, copies)| copies).sum();
+            assert_eq!(trace.instances, won + &BigUint::from(1));
+        }
+    }
+
+    #[test]
+    fn exports() {
+        let traces = traces();
+
+        let table = trace_table(&traces);
+        assert!(table.contains("   4 |       1 |         8 | 1 (x1), 2 (x2), 3 (x4)\n"));
+        assert!(table.contains("   6 |       0 |         1 | -\n"));
+
+        let dot = trace_dot(&traces);
+        assert!(dot.contains("  c4 [label=\"Card 4\\n8 instances\"];\n"));
+        assert!(dot.contains("  c3 -> c4 [label=\"4\"];\n"));
+    }
+
+    #[test]
+    fn traces_counts_past_u64() {
+        let cards: Vec<Scratchcard> = (1..=70)
+            .map(|id| {
+                let nums: Vec<u64> = (0..70 - id).collect();
+                Scratchcard::new(id, nums.clone(), nums).unwrap()
+            })
+            .collect();
+
+        let traces = trace_copies(&cards);
+        assert_eq!(traces[69].instances.to_string(), (1_u128 << 69).to_string());
+        assert_eq!(traces[69].won_from[68], (69, traces[68].instances.clone()));
+        assert!(trace_table(&traces).contains(&format!("  70 |       0 | {} |", 1_u128 << 69)));
+    }
+}
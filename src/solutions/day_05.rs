@@ -1,113 +1,337 @@
-use std::{collections::HashMap, ops::Range, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fmt::Display,
+    ops::Range,
+};
+
+use crate::utils::{interval::IntervalSet, range_map::RangeMap};
 
 use super::Solver;
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
-enum MapsIdentifier {
-    SeedToSoil,
-    SoilToFertilizer,
-    FertilizerToWater,
-    WaterToLight,
-    LightToTemperature,
-    TemperatureToHumidity,
-    HumidityToLocation,
+pub mod validation;
+
+/// One line of a map section: a source range and where it starts in the destination.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct MapEntry {
+    // 1-based position among its map's entries; blank lines and the seeds line don't reach
+    // the solver, so a line number here wouldn't match the file
+    index: usize,
+    // the entry as written, to point the user at it
+    text: String,
+    source: Range<u64>,
+    dest: u64,
 }
 
-impl FromStr for MapsIdentifier {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "seed-to-soil" => Ok(MapsIdentifier::SeedToSoil),
-            "soil-to-fertilizer" => Ok(MapsIdentifier::SoilToFertilizer),
-            "fertilizer-to-water" => Ok(MapsIdentifier::FertilizerToWater),
-            "water-to-light" => Ok(MapsIdentifier::WaterToLight),
-            "light-to-temperature" => Ok(MapsIdentifier::LightToTemperature),
-            "temperature-to-humidity" => Ok(MapsIdentifier::TemperatureToHumidity),
-            "humidity-to-location" => Ok(MapsIdentifier::HumidityToLocation),
-            _ => Err(()),
+type Sections = BTreeMap<(String, String), Vec<MapEntry>>;
+
+pub const SEED: &str = "seed";
+pub const LOCATION: &str = "location";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AlmanacError {
+    InvalidHeader(String),
+    InvalidEntry(String),
+    EntryBeforeHeader(String),
+    DuplicateMap { source: String, dest: String },
+    Cycle(Vec<String>),
+    NoPath { from: String, to: String },
+    Ambiguous(Vec<validation::Overlap>),
+}
+
+impl Display for AlmanacError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlmanacError::InvalidHeader(line) => write!(f, "invalid map header '{}'", line),
+            AlmanacError::InvalidEntry(line) => write!(f, "invalid map entry '{}'", line),
+            AlmanacError::EntryBeforeHeader(line) => {
+                write!(f, "map entry '{}' comes before any header", line)
+            }
+            AlmanacError::DuplicateMap { source, dest } => {
+                write!(f, "{}-to-{} is defined more than once", source, dest)
+            }
+            AlmanacError::Cycle(categories) => {
+                write!(f, "categories form a cycle: {}", categories.join(" -> "))
+            }
+            AlmanacError::NoPath { from, to } => write!(f, "no maps lead from {} to {}", from, to),
+            AlmanacError::Ambiguous(overlaps) => {
+                write!(f, "overlapping map entries:")?;
+                for overlap in overlaps {
+                    write!(f, "\n  {}", overlap)?;
+                }
+                Ok(())
+            }
         }
     }
 }
 
-struct Almanac {
-    maps: HashMap<MapsIdentifier, Vec<(Range<u64>, u64)>>,
+impl std::error::Error for AlmanacError {}
+
+/// Maps between categories, read from `source-to-destination map:` sections. The categories
+/// and maps form a directed graph that must not contain cycles.
+pub struct Almanac {
+    maps: BTreeMap<String, BTreeMap<String, RangeMap>>,
 }
 
 impl Almanac {
-    fn find_dest(&self, seed: u64) -> u64 {
-        [
-            MapsIdentifier::SeedToSoil,
-            MapsIdentifier::SoilToFertilizer,
-            MapsIdentifier::FertilizerToWater,
-            MapsIdentifier::WaterToLight,
-            MapsIdentifier::LightToTemperature,
-            MapsIdentifier::TemperatureToHumidity,
-            MapsIdentifier::HumidityToLocation,
-        ]
-        .iter()
-        .map(|maps_identifier| self.maps.get(maps_identifier).unwrap())
-        .fold(seed, |last_dest, maps| {
-            maps.iter()
-                .find_map(|(range, dest)| {
-                    if range.contains(&last_dest) {
-                        Some(last_dest - range.start + dest)
-                    } else {
-                        None
-                    }
-                })
-                .unwrap_or(last_dest)
+    /// The categories passed through going from `from` to `to`, including both ends. Takes the
+    /// fewest maps if there are several routes.
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<String>, AlmanacError> {
+        let mut came_from: HashMap<&str, &str> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut path = vec![to.to_string()];
+                let mut cur = to;
+                while cur != from {
+                    cur = came_from[cur];
+                    path.push(cur.to_string());
+                }
+                path.reverse();
+
+                return Ok(path);
+            }
+
+            for next in self.maps.get(category).into_iter().flat_map(|m| m.keys()) {
+                if next != from && !came_from.contains_key(next.as_str()) {
+                    came_from.insert(next, category);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        Err(AlmanacError::NoPath {
+            from: from.to_string(),
+            to: to.to_string(),
         })
     }
 
-    fn find_min_location_in_seed_range(&self, left: u64, right: u64) -> u64 {
-        self.find_min_location_in_seed_range_impl(left, right, u64::MAX)
-    }
-
-    fn find_min_location_in_seed_range_impl(&self, left: u64, right: u64, min: u64) -> u64 {
-        let left_val = self.find_dest(left);
-        let right_val = self.find_dest(right);
-
-        let is_linearly_increasing = right_val > left_val && right_val - left_val == right - left;
-        if is_linearly_increasing || right <= left {
-            // since a range should be linearly increasing, we can stop execution once we find a case where
-            // the difference between the two values is equal to the range size
-            return min.min(left_val);
-        };
-
-        let mid_point = (right - left) / 2;
-        let left_min = self.find_min_location_in_seed_range_impl(left, left + mid_point, min);
-        let right_min = self.find_min_location_in_seed_range_impl(left + mid_point + 1, right, min);
-        left_min.min(right_min)
-    }
-
-    fn new_from_lines<'a>(lines: &'a [&'a str]) -> Almanac {
-        let mut maps: HashMap<MapsIdentifier, Vec<(Range<u64>, u64)>> = HashMap::new();
-        let mut map_identifier: Option<MapsIdentifier> = None;
-        for line in lines {
-            if line.is_empty() {
-                // end of last map
-            } else if line.ends_with(':') {
-                // slicing to only get the map identifier (removing " map:")
-                map_identifier = Some(line[0..line.len() - 5].parse().unwrap());
-            } else {
-                let mut vals = line.split_ascii_whitespace();
-                let (dest, source, range): (u64, u64, u64) = (
-                    vals.next().unwrap().parse().unwrap(),
-                    vals.next().unwrap().parse().unwrap(),
-                    vals.next().unwrap().parse().unwrap(),
-                );
-                maps.entry(map_identifier.clone().expect("shouldn't be none"))
-                    .or_default()
-                    .push((source..(source + range), dest))
+    pub fn stages(&self, from: &str, to: &str) -> Result<Vec<&RangeMap>, AlmanacError> {
+        let path = self.path(from, to)?;
+
+        Ok(path.windows(2).map(|w| &self.maps[&w[0]][&w[1]]).collect())
+    }
+
+    pub fn find_dest(&self, value: u64, from: &str, to: &str) -> Result<u64, AlmanacError> {
+        Ok(self
+            .stages(from, to)?
+            .iter()
+            .fold(value, |last_dest, map| map.map(last_dest)))
+    }
+
+    /// Pushes every value through all the stages at once, so the result is exact no matter how
+    /// the maps split the ranges.
+    pub fn find_min_dest(
+        &self,
+        values: &IntervalSet,
+        from: &str,
+        to: &str,
+    ) -> Result<Option<u64>, AlmanacError> {
+        Ok(self
+            .stages(from, to)?
+            .iter()
+            .fold(values.clone(), |last_dest, map| map.map_set(&last_dest))
+            .min())
+    }
+
+    /// Runs the stages backwards: every `from` value that ends up somewhere in `dests`.
+    pub fn find_sources(
+        &self,
+        dests: &IntervalSet,
+        from: &str,
+        to: &str,
+    ) -> Result<IntervalSet, AlmanacError> {
+        Ok(self
+            .stages(from, to)?
+            .iter()
+            .rev()
+            .fold(dests.clone(), |last_sources, map| {
+                map.preimage(&last_sources)
+            }))
+    }
+
+    /// The same answer as `find_min_dest`, found by walking the destinations upwards a block at
+    /// a time until one block comes from a value in `values`. Works backwards from the
+    /// destinations rather than forwards from `values`, so the two can check each other.
+    pub fn scan_min_dest(
+        &self,
+        values: &IntervalSet,
+        from: &str,
+        to: &str,
+    ) -> Result<Option<u64>, AlmanacError> {
+        let composed = self.composed(from, to)?;
+
+        // within a block the same pieces and identity gaps land on every value, so one
+        // preimage tells whether anything in the block comes from `values`
+        let mut breakpoints = composed.breakpoints();
+        breakpoints.extend(composed.pieces().iter().flat_map(|p| {
+            let image = p.image();
+            [image.start, image.end]
+        }));
+
+        for block in IntervalSet::from(0..u64::MAX).split_at(&breakpoints) {
+            let block = IntervalSet::from(block);
+            let hit = composed.preimage(&block).intersection(values);
+            if !hit.is_empty() {
+                return Ok(composed.map_set(&hit).intersection(&block).min());
             }
         }
 
-        maps.values_mut()
-            .into_iter()
-            .for_each(|map| map.sort_by_key(|k| k.0.start));
-        Almanac { maps }
+        Ok(None)
+    }
+
+    /// All the stages between two categories folded into one map.
+    pub fn composed(&self, from: &str, to: &str) -> Result<RangeMap, AlmanacError> {
+        Ok(self
+            .stages(from, to)?
+            .iter()
+            .fold(RangeMap::default(), |acc, map| acc.compose(map)))
+    }
+
+    /// Reads every map section. Where entries within a map overlap the first match wins, as
+    /// `RangeMap` does; see `new_strict` to reject those instead.
+    pub fn new_from_lines<'a>(lines: &'a [&'a str]) -> Result<Almanac, AlmanacError> {
+        Almanac::from_sections(parse_sections(lines)?)
+    }
+
+    /// Like `new_from_lines`, but fails listing the offending entries if any map has entries
+    /// whose source ranges overlap.
+    pub fn new_strict<'a>(lines: &'a [&'a str]) -> Result<Almanac, AlmanacError> {
+        let sections = parse_sections(lines)?;
+
+        let overlaps = validation::find_overlaps(&sections);
+        if !overlaps.is_empty() {
+            return Err(AlmanacError::Ambiguous(overlaps));
+        }
+
+        Almanac::from_sections(sections)
+    }
+
+    fn from_sections(sections: Sections) -> Result<Almanac, AlmanacError> {
+        let mut maps: BTreeMap<String, BTreeMap<String, RangeMap>> = BTreeMap::new();
+        for ((source, dest), entries) in sections {
+            maps.entry(source).or_default().insert(
+                dest,
+                RangeMap::new(entries.into_iter().map(|e| (e.source, e.dest))),
+            );
+        }
+
+        let almanac = Almanac { maps };
+        almanac.check_acyclic()?;
+
+        Ok(almanac)
+    }
+
+    fn check_acyclic(&self) -> Result<(), AlmanacError> {
+        fn visit<'a>(
+            almanac: &'a Almanac,
+            category: &'a str,
+            stack: &mut Vec<&'a str>,
+            done: &mut HashSet<&'a str>,
+        ) -> Result<(), AlmanacError> {
+            if let Some(pos) = stack.iter().position(|&c| c == category) {
+                let mut cycle: Vec<String> = stack[pos..].iter().map(|c| c.to_string()).collect();
+                cycle.push(category.to_string());
+                return Err(AlmanacError::Cycle(cycle));
+            }
+            if !done.insert(category) {
+                return Ok(());
+            }
+
+            stack.push(category);
+            for next in almanac
+                .maps
+                .get(category)
+                .into_iter()
+                .flat_map(|m| m.keys())
+            {
+                visit(almanac, next, stack, done)?;
+            }
+            stack.pop();
+
+            Ok(())
+        }
+
+        let mut done = HashSet::new();
+        for category in self.maps.keys() {
+            visit(self, category, &mut vec![], &mut done)?;
+        }
+
+        Ok(())
+    }
+}
+
+// groups the entries by map, without interpreting them yet
+fn parse_sections(lines: &[&str]) -> Result<Sections, AlmanacError> {
+    let mut entries: Sections = BTreeMap::new();
+    let mut map_identifier: Option<(String, String)> = None;
+    for line in lines {
+        if line.is_empty() {
+            // end of last map
+        } else if let Some(header) = line.strip_suffix("map:") {
+            let (source, dest) = header
+                .trim()
+                .split_once("-to-")
+                .filter(|(source, dest)| !source.is_empty() && !dest.is_empty())
+                .ok_or_else(|| AlmanacError::InvalidHeader(line.to_string()))?;
+
+            let identifier = (source.to_string(), dest.to_string());
+            if entries.insert(identifier.clone(), vec![]).is_some() {
+                return Err(AlmanacError::DuplicateMap {
+                    source: identifier.0,
+                    dest: identifier.1,
+                });
+            }
+            map_identifier = Some(identifier);
+        } else {
+            let identifier = map_identifier
+                .as_ref()
+                .ok_or_else(|| AlmanacError::EntryBeforeHeader(line.to_string()))?;
+
+            let vals = line
+                .split_ascii_whitespace()
+                .map(|v| v.parse().ok())
+                .collect::<Option<Vec<u64>>>();
+            let (dest, source, range) = match vals.as_deref() {
+                Some(&[dest, source, range]) => (dest, source, range),
+                _ => return Err(AlmanacError::InvalidEntry(line.to_string())),
+            };
+            // both the source and destination ranges have to fit in a u64
+            let end = source
+                .checked_add(range)
+                .filter(|_| dest.checked_add(range).is_some())
+                .ok_or_else(|| AlmanacError::InvalidEntry(line.to_string()))?;
+
+            let map_entries = entries.get_mut(identifier).unwrap();
+            map_entries.push(MapEntry {
+                index: map_entries.len() + 1,
+                text: line.to_string(),
+                source: source..end,
+                dest,
+            })
+        }
     }
+
+    Ok(entries)
+}
+
+/// The `seeds:` line read as pairs of range start and length.
+fn parse_seed_ranges(line: &str) -> Result<IntervalSet, ()> {
+    let seeds = line
+        .split_once(':')
+        .ok_or(())?
+        .1
+        .split_ascii_whitespace()
+        .map(|s| s.parse().map_err(|_| ()))
+        .collect::<Result<Vec<u64>, ()>>()?;
+
+    seeds
+        .chunks(2)
+        .map(|chunk| match *chunk {
+            [start, len] => Ok(start..start.checked_add(len).ok_or(())?),
+            _ => Err(()),
+        })
+        .collect()
 }
 
 pub struct DayFiveSolver {}
@@ -122,11 +346,14 @@ impl<'a> Solver<'a> for DayFiveSolver {
             .map(|s| s.parse().map_err(|_| ()))
             .collect::<Result<Vec<u64>, ()>>()?;
 
-        let almanac = Almanac::new_from_lines(&input[1..]);
+        let seed_to_location = Almanac::new_from_lines(&input[1..])
+            .map_err(|_| ())?
+            .composed(SEED, LOCATION)
+            .map_err(|_| ())?;
 
         let res = seeds
             .iter()
-            .map(|seed| almanac.find_dest(*seed))
+            .map(|seed| seed_to_location.map(*seed))
             .min()
             .unwrap();
 
@@ -134,33 +361,101 @@ impl<'a> Solver<'a> for DayFiveSolver {
     }
 
     fn part_2(&self, input: &'a [&'a str]) -> Result<String, ()> {
-        let seed_ranges: Vec<(u64, u64)> = input[0]
-            .split(':')
-            .nth(1)
-            .unwrap()
-            .split_ascii_whitespace()
-            .map(|s| s.parse().map_err(|_| ()))
-            .collect::<Result<Vec<u64>, ()>>()?
-            .chunks(2)
-            .map(|chunk| (chunk[0], chunk[1]))
-            .collect();
+        let seeds = parse_seed_ranges(input[0])?;
 
-        let almanac = Almanac::new_from_lines(&input[1..]);
+        let seed_to_location = Almanac::new_from_lines(&input[1..])
+            .map_err(|_| ())?
+            .composed(SEED, LOCATION)
+            .map_err(|_| ())?;
 
-        let mut min = u64::MAX;
-        for (seed_start, range) in seed_ranges {
-            min = min.min(almanac.find_min_location_in_seed_range(seed_start, seed_start + range))
-        }
+        let min = seed_to_location.map_set(&seeds).min().ok_or(())?;
 
         Ok(min.to_string())
     }
+
+    fn run_mode(&self, mode: &str, args: &[&str], input: &'a [&'a str]) -> Result<String, ()> {
+        match mode {
+            "composed" => {
+                // optionally `composed <from> <to>` for any two categories
+                let (from, to) = match args {
+                    [from, to] => (*from, *to),
+                    _ => (SEED, LOCATION),
+                };
+                let composed = Almanac::new_from_lines(&input[1..])
+                    .map_err(|_| ())?
+                    .composed(from, to)
+                    .map_err(|_| ())?;
+
+                let mut out = format!("{} -> offset (unlisted values keep their value)\n", from);
+                for piece in composed.pieces() {
+                    out.push_str(&format!(
+                        "{}..{} -> {:+}\n",
+                        piece.source.start,
+                        piece.source.end,
+                        piece.offset()
+                    ));
+                }
+
+                Ok(out)
+            }
+            // part 2 again, searching locations upwards instead of mapping the seed ranges
+            "scan" => {
+                let seeds = parse_seed_ranges(input[0])?;
+                let almanac = Almanac::new_from_lines(&input[1..]).map_err(|_| ())?;
+
+                let min = almanac
+                    .scan_min_dest(&seeds, SEED, LOCATION)
+                    .map_err(|_| ())?
+                    .ok_or(())?;
+
+                Ok(min.to_string())
+            }
+            // `sources <location>`: every seed that ends up at the given location
+            "sources" => {
+                let location: u64 = args.first().ok_or(())?.parse().map_err(|_| ())?;
+                // sets are half-open, so `u64::MAX` itself can't be looked up
+                let end = location.checked_add(1).ok_or(())?;
+                let almanac = Almanac::new_from_lines(&input[1..]).map_err(|_| ())?;
+
+                let seeds = almanac
+                    .find_sources(&IntervalSet::from(location..end), SEED, LOCATION)
+                    .map_err(|_| ())?;
+
+                let mut out = String::new();
+                for r in seeds.ranges() {
+                    out.push_str(&format!("{}..{}\n", r.start, r.end));
+                }
+
+                Ok(out)
+            }
+            // `validate [gaps] [strict]`: overlapping entries, and optionally identity gaps, in
+            // each map; `strict` also says whether `Almanac::new_strict` accepts the almanac
+            "validate" => {
+                let report =
+                    validation::validate(&input[1..], args.contains(&"gaps")).map_err(|_| ())?;
+
+                let mut out = report.to_string();
+                if args.contains(&"strict") {
+                    match Almanac::new_strict(&input[1..]) {
+                        Ok(_) => out.push_str("strict: accepted\n"),
+                        Err(e) => out.push_str(&format!("strict: rejected, {}\n", e)),
+                    }
+                }
+
+                Ok(out)
+            }
+            _ => Err(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::solutions::{normalize_input, Solver};
 
-    use super::DayFiveSolver;
+    use crate::utils::interval::IntervalSet;
+
+    use super::{Almanac, AlmanacError, DayFiveSolver, LOCATION, SEED};
 
     const INPUT: &str = "
         seeds: 79 14 55 13
@@ -217,4 +512,188 @@ mod tests {
 
         assert_eq!(res, "46");
     }
+
+    #[test]
+    fn seed_ranges_match_brute_force() {
+        let input = normalize_input(INPUT).unwrap();
+        let almanac = Almanac::new_from_lines(&input[1..]).unwrap();
+
+        for seeds in [
+            vec![79..93, 55..68],
+            vec![0..60, 60..120],
+            vec![13..15, 90..101],
+            // far past every map, where scanning one location at a time would never finish
+            vec![5_000_000_000..5_000_000_003, 6_000_000_000..6_000_000_002],
+            vec![],
+        ] {
+            let brute_force = seeds
+                .iter()
+                .flat_map(|r| r.clone())
+                .map(|seed| almanac.find_dest(seed, SEED, LOCATION).unwrap())
+                .min();
+
+            let seeds = IntervalSet::from_ranges(seeds);
+            assert_eq!(
+                almanac.find_min_dest(&seeds, SEED, LOCATION).unwrap(),
+                brute_force
+            );
+            assert_eq!(
+                almanac.scan_min_dest(&seeds, SEED, LOCATION).unwrap(),
+                brute_force
+            );
+        }
+    }
+
+    #[test]
+    fn composed_map_matches_stages() {
+        let input = normalize_input(INPUT).unwrap();
+        let almanac = Almanac::new_from_lines(&input[1..]).unwrap();
+        let composed = almanac.composed(SEED, LOCATION).unwrap();
+
+        for seed in 0..120 {
+            assert_eq!(
+                composed.map(seed),
+                almanac.find_dest(seed, SEED, LOCATION).unwrap()
+            );
+        }
+        assert_eq!(composed.map(1_000), 1_000);
+    }
+
+    #[test]
+    fn resolves_paths_between_any_categories() {
+        let input = normalize_input(INPUT).unwrap();
+        let almanac = Almanac::new_from_lines(&input[1..]).unwrap();
+
+        assert_eq!(
+            almanac.path("soil", "humidity").unwrap(),
+            vec![
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity"
+            ]
+        );
+        // soil 81 -> fertilizer 81 -> water 81 -> light 74 -> temperature 78 -> humidity 78
+        assert_eq!(almanac.find_dest(81, "soil", "humidity"), Ok(78));
+        assert_eq!(
+            almanac.path("location", "seed"),
+            Err(AlmanacError::NoPath {
+                from: "location".to_string(),
+                to: "seed".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_bad_graphs() {
+        let cyclic = ["a-to-b map:", "1 2 3", "b-to-c map:", "c-to-a map:"];
+        assert_eq!(
+            Almanac::new_from_lines(&cyclic).err(),
+            Some(AlmanacError::Cycle(vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "a".to_string()
+            ]))
+        );
+
+        let duplicated = ["a-to-b map:", "a-to-b map:"];
+        assert!(matches!(
+            Almanac::new_from_lines(&duplicated),
+            Err(AlmanacError::DuplicateMap { .. })
+        ));
+
+        let headless = ["1 2 3"];
+        assert!(matches!(
+            Almanac::new_from_lines(&headless),
+            Err(AlmanacError::EntryBeforeHeader(_))
+        ));
+
+        let malformed = ["a-to-b map:", "1 2"];
+        assert!(matches!(
+            Almanac::new_from_lines(&malformed),
+            Err(AlmanacError::InvalidEntry(_))
+        ));
+
+        let overflowing = ["seed-to-location map:", "18446744073709551610 0 10"];
+        assert_eq!(
+            Almanac::new_from_lines(&overflowing).err(),
+            Some(AlmanacError::InvalidEntry(
+                "18446744073709551610 0 10".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn runs_backwards() {
+        let input = normalize_input(INPUT).unwrap();
+        let almanac = Almanac::new_from_lines(&input[1..]).unwrap();
+
+        let locations = IntervalSet::from_ranges([40..50, 80..90]);
+        let seeds = almanac.find_sources(&locations, SEED, LOCATION).unwrap();
+        for seed in 0..120 {
+            let location = almanac.find_dest(seed, SEED, LOCATION).unwrap();
+            assert_eq!(
+                seeds.contains(seed),
+                locations.contains(location),
+                "{}",
+                seed
+            );
+        }
+
+        // the part 2 answer, location 46, comes from seed 82
+        let seeds = almanac
+            .find_sources(&IntervalSet::from(46..47), SEED, LOCATION)
+            .unwrap();
+        assert!(seeds.contains(82));
+
+        let solver = DayFiveSolver {};
+        assert_eq!(solver.run_mode("scan", &[], &input), Ok("46".to_string()));
+        assert_eq!(
+            solver.run_mode("sources", &["46"], &input),
+            Ok("82..83\n".to_string())
+        );
+        assert_eq!(
+            solver.run_mode("sources", &[&u64::MAX.to_string()], &input),
+            Err(())
+        );
+    }
+
+    #[test]
+    fn validate_mode_numbers_entries_within_their_map() {
+        let input = normalize_input(
+            "seeds: 1 2
+
+            seed-to-location map:
+            10 0 5
+            20 3 5",
+        )
+        .unwrap();
+
+        let solver = DayFiveSolver {};
+        assert_eq!(
+            solver.run_mode("validate", &[], &input),
+            Ok(
+                "overlap seed-to-location: entry 1 '10 0 5' and entry 2 '20 3 5' both map 3..5\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            solver.run_mode("validate", &["strict"], &input),
+            Ok(
+                "overlap seed-to-location: entry 1 '10 0 5' and entry 2 '20 3 5' both map 3..5\n\
+                 strict: rejected, overlapping map entries:\n  \
+                 seed-to-location: entry 1 '10 0 5' and entry 2 '20 3 5' both map 3..5\n"
+                    .to_string()
+            )
+        );
+
+        let input = normalize_input(INPUT).unwrap();
+        assert_eq!(
+            solver.run_mode("validate", &["strict"], &input),
+            Ok("no problems found\nstrict: accepted\n".to_string())
+        );
+    }
 }
@@ -0,0 +1,210 @@
+use std::{fmt::Display, ops::Range};
+
+use crate::utils::interval::IntervalSet;
+
+use super::{parse_sections, AlmanacError, Sections};
+
+/// Two entries of the same map that both claim the values in `values`. Entries are numbered
+/// from 1 in the order they're listed under the map's header, and `lines` holds each one as
+/// written, `dest source length`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Overlap {
+    pub map: String,
+    pub entries: (usize, usize),
+    pub lines: (String, String),
+    pub values: Range<u64>,
+}
+
+impl Display for Overlap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: entry {} '{}' and entry {} '{}' both map {}..{}",
+            self.map,
+            self.entries.0,
+            self.lines.0,
+            self.entries.1,
+            self.lines.1,
+            self.values.start,
+            self.values.end
+        )
+    }
+}
+
+/// Values below a map's last entry that no entry covers, so they map to themselves.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Gap {
+    pub map: String,
+    pub values: Range<u64>,
+}
+
+impl Display for Gap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}..{} maps to itself",
+            self.map, self.values.start, self.values.end
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Report {
+    pub overlaps: Vec<Overlap>,
+    pub gaps: Vec<Gap>,
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.overlaps.is_empty() && self.gaps.is_empty() {
+            return writeln!(f, "no problems found");
+        }
+
+        for overlap in &self.overlaps {
+            writeln!(f, "overlap {}", overlap)?;
+        }
+        for gap in &self.gaps {
+            writeln!(f, "gap {}", gap)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks every map in the almanac for entries with overlapping source ranges and, if
+/// `include_gaps` is set, for identity gaps between them.
+pub fn validate(lines: &[&str], include_gaps: bool) -> Result<Report, AlmanacError> {
+    let sections = parse_sections(lines)?;
+
+    Ok(Report {
+        overlaps: find_overlaps(&sections),
+        gaps: if include_gaps {
+            find_gaps(&sections)
+        } else {
+            vec![]
+        },
+    })
+}
+
+pub(super) fn find_overlaps(sections: &Sections) -> Vec<Overlap> {
+    let mut overlaps = vec![];
+
+    for ((source, dest), entries) in sections {
+        let mut entries: Vec<_> = entries.iter().collect();
+        entries.sort_by_key(|e| (e.source.start, e.index));
+
+        for (i, a) in entries.iter().enumerate() {
+            // sorted by start, so everything after `a` that starts before it ends overlaps it
+            for b in entries[i + 1..]
+                .iter()
+                .take_while(|b| b.source.start < a.source.end)
+            {
+                if b.source.is_empty() {
+                    continue;
+                }
+
+                let (first, second) = if a.index < b.index { (a, b) } else { (b, a) };
+                overlaps.push(Overlap {
+                    map: format!("{}-to-{}", source, dest),
+                    entries: (first.index, second.index),
+                    lines: (first.text.clone(), second.text.clone()),
+                    values: b.source.start..a.source.end.min(b.source.end),
+                });
+            }
+        }
+    }
+
+    overlaps
+}
+
+fn find_gaps(sections: &Sections) -> Vec<Gap> {
+    let mut gaps = vec![];
+
+    for ((source, dest), entries) in sections {
+        let covered = IntervalSet::from_ranges(entries.iter().map(|e| e.source.clone()));
+        let Some(max) = covered.max() else {
+            continue;
+        };
+
+        let uncovered = IntervalSet::from(0..max + 1).difference(&covered);
+        gaps.extend(uncovered.ranges().iter().map(|r| Gap {
+            map: format!("{}-to-{}", source, dest),
+            values: r.clone(),
+        }));
+    }
+
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solutions::day_05::{Almanac, AlmanacError};
+
+    use super::{validate, Gap, Overlap};
+
+    const MAPS: [&str; 6] = [
+        "seed-to-soil map:",
+        "50 98 2",
+        "52 50 48",
+        "soil-to-fertilizer map:",
+        "0 10 20",
+        "100 30 10",
+    ];
+
+    #[test]
+    fn reports_gaps() {
+        let report = validate(&MAPS, true).unwrap();
+
+        assert!(report.overlaps.is_empty());
+        assert_eq!(
+            report.gaps,
+            vec![
+                Gap {
+                    map: "seed-to-soil".to_string(),
+                    values: 0..50
+                },
+                Gap {
+                    map: "soil-to-fertilizer".to_string(),
+                    values: 0..10
+                },
+            ]
+        );
+        assert!(validate(&MAPS, false).unwrap().gaps.is_empty());
+    }
+
+    #[test]
+    fn strict_mode_rejects_overlaps() {
+        let mut lines = MAPS.to_vec();
+        lines.push("7 20 10");
+        lines.push("5 0 100");
+
+        let text = ["0 10 20", "100 30 10", "7 20 10", "5 0 100"];
+        let overlaps: Vec<Overlap> = [
+            ((1, 4), 10..30),
+            ((3, 4), 20..30),
+            ((2, 4), 30..40),
+            ((1, 3), 20..30),
+        ]
+        .into_iter()
+        .map(|(entries, values)| Overlap {
+            map: "soil-to-fertilizer".to_string(),
+            entries,
+            lines: (
+                text[entries.0 - 1].to_string(),
+                text[entries.1 - 1].to_string(),
+            ),
+            values,
+        })
+        .collect();
+        assert_eq!(validate(&lines, false).unwrap().overlaps, overlaps);
+        match Almanac::new_strict(&lines) {
+            Err(AlmanacError::Ambiguous(found)) => assert_eq!(found, overlaps),
+            _ => panic!("expected overlaps"),
+        }
+
+        // the lenient parser still takes the first match
+        let almanac = Almanac::new_from_lines(&lines).unwrap();
+        assert_eq!(almanac.find_dest(12, "soil", "fertilizer"), Ok(17));
+        assert!(Almanac::new_strict(&MAPS).is_ok());
+    }
+}
@@ -1,83 +1,101 @@
+use self::{
+    physics::RaceModel,
+    table::{Kerning, RaceTable},
+};
+
 use super::Solver;
 
+pub mod physics;
+pub mod table;
+
 pub struct DaySixSolver {}
 
-fn find_solutions(distance: u64, total_duration: u64) -> Option<u64> {
+/// How many whole milliseconds of holding the button beat `record` in a race lasting
+/// `total_duration`. Exact for any `u64` inputs.
+fn find_solutions(record: u64, total_duration: u64) -> u64 {
     // v0 = t, where t = how long the button is pressed
     // distance = v0*(total_duration - t), where total_duration is the length
-    //   of the race and d is the distance we're solving for
+    //   of the race
     //
     // distance = t(total_duration - t) = t*total_duration - t^2
-    // solving for t will give us the min and max values we can hold
-    // the button for.
-
-    let a = 1.0;
-    let b = -(total_duration as f64);
-    let c = distance as f64;
+    // this is symmetric around total_duration / 2, so if t_start is the shortest winning hold
+    // then total_duration - t_start is the longest.
+    //
+    // everything is done in u128 since total_duration^2 doesn't fit in a u64.
+    let total = total_duration as u128;
+    let target = record as u128 + 1;
+    let distance = |t: u128| t * (total - t);
 
-    let discriminant = b * b - 4.0 * a * c;
+    if distance(total / 2) < target {
+        return 0;
+    }
 
-    if discriminant < 0.0 {
-        // No real roots
-        None
-    } else {
-        let t_start = (-b - discriminant.sqrt()) / (2.0 * a);
-        let t_end = (-b + discriminant.sqrt()) / (2.0 * a);
+    // t_start = (total - sqrt(total^2 - 4 * target)) / 2, which can't go negative here since
+    // the best hold already reaches the target. The integer square root is rounded down, so
+    // nudge the estimate onto the exact boundary.
+    let discriminant = total * total - 4 * target;
+    let mut t_start = (total - discriminant.isqrt()) / 2;
+    while distance(t_start) < target {
+        t_start += 1;
+    }
+    while t_start > 0 && distance(t_start - 1) >= target {
+        t_start -= 1;
+    }
 
-        let t_start = t_start;
-        let t_end = t_end;
+    (total - 2 * t_start + 1) as u64
+}
 
-        Some(t_end.floor() as u64 - t_start.ceil() as u64 + 1)
-    }
+// part 1 under any race model
+fn product_of_wins(input: &[&str], model: &RaceModel) -> Result<u64, ()> {
+    let races = RaceTable::parse(input)
+        .and_then(|table| table.races(Kerning::Columns))
+        .map_err(|_| ())?;
+
+    races
+        .iter()
+        .map(|race| model.count_wins(race.record, race.time))
+        .try_fold(1_u64, |acc, wins| acc.checked_mul(wins))
+        .ok_or(())
 }
 
 impl<'a> Solver<'a> for DaySixSolver {
     fn part_1(&self, input: &'a [&'a str]) -> Result<String, ()> {
-        let times = input[0]
-            .split_whitespace()
-            .skip(1)
-            .map(|x| x.parse::<u64>().unwrap())
-            .collect::<Vec<u64>>();
-        let distances = input[1]
-            .split_whitespace()
-            .skip(1)
-            .map(|x| x.parse::<u64>().unwrap())
-            .collect::<Vec<u64>>();
-
-        let res: u64 = times
-            .iter()
-            .zip(distances.iter())
-            .map(|(&t, &d)| {
-                // We want to beat the previous record by at least one.
-                let target_distance = d + 1;
-                find_solutions(target_distance, t).unwrap()
-            })
-            .reduce(|acc, solution| acc * solution)
-            .unwrap();
+        let res = product_of_wins(input, &RaceModel::default())?;
 
         Ok(res.to_string())
     }
 
     fn part_2(&self, input: &'a [&'a str]) -> Result<String, ()> {
-        let time: u64 = input[0]
-            .split_whitespace()
-            .skip(1)
-            .collect::<Vec<&str>>()
-            .join("")
-            .parse()
-            .unwrap();
-        let distance: u64 = input[1]
-            .split_whitespace()
-            .skip(1)
-            .collect::<Vec<&str>>()
-            .join("")
-            .parse()
-            .unwrap();
+        let races = RaceTable::parse(input)
+            .and_then(|table| table.races(Kerning::Kerned))
+            .map_err(|_| ())?;
 
-        let res = find_solutions(distance + 1, time).unwrap();
+        let res = RaceModel::default().count_wins(races[0].record, races[0].time);
 
         Ok(res.to_string())
     }
+
+    fn run_mode(&self, mode: &str, args: &[&str], input: &'a [&'a str]) -> Result<String, ()> {
+        match mode {
+            // `model <acceleration> <max speed or "none"> <drag>`: part 1 with different boats
+            "model" => {
+                let [acceleration, max_speed, drag] = args else {
+                    return Err(());
+                };
+                let model = RaceModel {
+                    acceleration: acceleration.parse().map_err(|_| ())?,
+                    max_speed: match *max_speed {
+                        "none" => None,
+                        max => Some(max.parse().map_err(|_| ())?),
+                    },
+                    drag: drag.parse().map_err(|_| ())?,
+                };
+
+                Ok(product_of_wins(input, &model)?.to_string())
+            }
+            _ => Err(()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -108,4 +126,88 @@ mod tests {
 
         assert_eq!(res, "71503".to_string());
     }
+
+    #[test]
+    fn malformed_tables_are_errors() {
+        let solver = DaySixSolver {};
+
+        assert_eq!(solver.part_1(&["Time: 7 15", "Distance: 9"]), Err(()));
+        assert_eq!(solver.part_2(&["Distance: 9"]), Err(()));
+    }
+
+    #[test]
+    fn other_race_models() {
+        let input = normalize_input(INPUT).unwrap();
+        let solver = DaySixSolver {};
+
+        let res = solver
+            .run_mode("model", &["1", "none", "0"], &input)
+            .unwrap();
+        assert_eq!(res, "288".to_string());
+
+        // boats twice as quick off the mark win more often
+        let res = solver
+            .run_mode("model", &["2", "none", "0"], &input)
+            .unwrap();
+        assert_eq!(res, (6 * 12 * 23).to_string());
+    }
+
+    fn brute_force(record: u64, total_duration: u64) -> u64 {
+        (0..=total_duration)
+            .filter(|t| t * (total_duration - t) > record)
+            .count() as u64
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for total_duration in 0..80 {
+            let best = (total_duration / 2) * (total_duration - total_duration / 2);
+            for record in 0..best + 3 {
+                assert_eq!(
+                    find_solutions(record, total_duration),
+                    brute_force(record, total_duration),
+                    "record {}, duration {}",
+                    record,
+                    total_duration
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn exact_for_huge_races() {
+        // `(total_duration, hold time)` pairs whose distances sit between 2^53 and 2^64, where
+        // an f64 can't tell neighbouring records apart, chosen so every record fits in a u64
+        let cases: [(u64, u64); 6] = [
+            (u64::MAX, 1),
+            (1 << 33, (1 << 32) - 2),
+            ((1 << 33) + 1, (1 << 32) - (1 << 20)),
+            ((1 << 53) + 1, 2_000),
+            ((1 << 54) + 3, 1_000),
+            (1 << 60, 15),
+        ];
+
+        for (total_duration, t) in cases {
+            let total = total_duration as u128;
+            let distance = |t: u128| t * (total - t);
+
+            // records right at, and either side of, the distance for the hold time
+            let reached = distance(t as u128);
+            for record in [reached - 1, reached, reached + 1] {
+                let record = u64::try_from(record).expect("every case's records fit in a u64");
+                let wins = find_solutions(record, total_duration) as u128;
+
+                // the wins are exactly the holds from t_start to total_duration - t_start
+                let t_start = (total + 1 - wins) / 2;
+                assert_eq!(total + 1 - wins, 2 * t_start);
+                assert!(distance(t_start) > record as u128);
+                assert!(distance(t_start - 1) <= record as u128);
+                assert!(distance(total - t_start) > record as u128);
+                assert!(distance(total - t_start + 1) <= record as u128);
+            }
+        }
+
+        assert_eq!(find_solutions(u64::MAX, u64::MAX), u64::MAX - 3);
+        assert_eq!(find_solutions(0, u64::MAX), u64::MAX - 1);
+    }
 }
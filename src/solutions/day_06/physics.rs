@@ -0,0 +1,156 @@
+use super::find_solutions;
+
+/// How a boat builds up speed while the button is held, and how it slows down once released.
+/// The puzzle's boats are `RaceModel::default()`: one unit of speed per millisecond held, no
+/// top speed and no drag.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RaceModel {
+    /// Speed gained per millisecond the button is held.
+    pub acceleration: u64,
+    pub max_speed: Option<u64>,
+    /// Speed lost at the end of every millisecond after release, until the boat stops.
+    pub drag: u64,
+}
+
+impl Default for RaceModel {
+    fn default() -> Self {
+        RaceModel {
+            acceleration: 1,
+            max_speed: None,
+            drag: 0,
+        }
+    }
+}
+
+impl RaceModel {
+    pub fn speed(&self, hold: u64) -> u128 {
+        let speed = self.acceleration as u128 * hold as u128;
+        match self.max_speed {
+            Some(max) => speed.min(max as u128),
+            None => speed,
+        }
+    }
+
+    /// How far the boat gets if the button is held for `hold` out of `duration` milliseconds.
+    /// Saturates rather than overflowing, which is far past any `u64` record anyway.
+    pub fn distance(&self, hold: u64, duration: u64) -> u128 {
+        let speed = self.speed(hold);
+        let moving = duration.saturating_sub(hold) as u128;
+
+        if self.drag == 0 {
+            return speed.saturating_mul(moving);
+        }
+
+        // speed, speed - drag, speed - 2 * drag... for as long as that stays positive
+        let drag = self.drag as u128;
+        let steps = moving.min(speed.div_ceil(drag));
+        let lost = drag.saturating_mul(steps * steps.saturating_sub(1) / 2);
+
+        speed.saturating_mul(steps).saturating_sub(lost)
+    }
+
+    /// How many whole milliseconds of holding the button beat `record`.
+    pub fn count_wins(&self, record: u64, duration: u64) -> u64 {
+        if self.acceleration == 0 {
+            return 0;
+        }
+
+        if self.max_speed.is_none() && self.drag == 0 {
+            // acceleration * t * (duration - t) > record exactly when
+            // t * (duration - t) > record / acceleration, rounding down
+            return find_solutions(record / self.acceleration, duration);
+        }
+
+        // otherwise the distance rises while the extra speed is worth the lost time and falls
+        // after, so search for the peak and then each edge of the winning holds
+        let distance = |hold| self.distance(hold, duration);
+        let record = record as u128;
+
+        let peak = first_true(0, duration, |hold| distance(hold + 1) <= distance(hold));
+        if distance(peak) <= record {
+            return 0;
+        }
+
+        let first = first_true(0, peak, |hold| distance(hold) > record);
+        let last = first_true(peak, duration, |hold| distance(hold + 1) <= record);
+
+        last - first + 1
+    }
+}
+
+// the smallest value in `lo..=hi` where `pred` holds, assuming it switches from false to true
+// at most once and holds at `hi`
+fn first_true(mut lo: u64, mut hi: u64, pred: impl Fn(u64) -> bool) -> u64 {
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    lo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RaceModel;
+
+    fn brute_force(model: &RaceModel, record: u64, duration: u64) -> u64 {
+        (0..=duration)
+            .filter(|&hold| model.distance(hold, duration) > record as u128)
+            .count() as u64
+    }
+
+    #[test]
+    fn default_model_is_the_puzzle() {
+        let model = RaceModel::default();
+
+        assert_eq!(model.distance(2, 7), 10);
+        assert_eq!(model.count_wins(9, 7), 4);
+        assert_eq!(model.count_wins(200, 30), 9);
+    }
+
+    #[test]
+    fn distance_with_drag() {
+        let model = RaceModel {
+            acceleration: 3,
+            max_speed: Some(10),
+            drag: 4,
+        };
+
+        // speed 9: travels 9 + 5 + 1 before stopping
+        assert_eq!(model.distance(3, 20), 15);
+        // speed capped at 10, but only 2ms left to move
+        assert_eq!(model.distance(18, 20), 16);
+        assert_eq!(model.distance(20, 20), 0);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for acceleration in 0..4 {
+            for max_speed in [None, Some(1), Some(5), Some(12)] {
+                for drag in 0..4 {
+                    let model = RaceModel {
+                        acceleration,
+                        max_speed,
+                        drag,
+                    };
+                    for duration in 0..30 {
+                        for record in 0..120 {
+                            assert_eq!(
+                                model.count_wins(record, duration),
+                                brute_force(&model, record, duration),
+                                "{:?}, record {}, duration {}",
+                                model,
+                                record,
+                                duration
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::fmt::Display;
+
+/// How to read the numbers on each row: as separate races, one per column, or as a single
+/// race whose digits were spread out by bad kerning.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Kerning {
+    Columns,
+    Kerned,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Race {
+    pub time: u64,
+    pub record: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RaceTableError {
+    MissingLabel { line: usize },
+    UnknownRow { line: usize, label: String },
+    DuplicateRow { line: usize, label: String },
+    MissingRow(&'static str),
+    InvalidNumber { line: usize, value: String },
+    ColumnMismatch { times: usize, distances: usize },
+    NoRaces,
+    Overflow { label: &'static str },
+}
+
+impl Display for RaceTableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RaceTableError::MissingLabel { line } => {
+                write!(f, "line {}: expected a 'Label:' before the numbers", line)
+            }
+            RaceTableError::UnknownRow { line, label } => {
+                write!(f, "line {}: unknown row '{}'", line, label)
+            }
+            RaceTableError::DuplicateRow { line, label } => {
+                write!(f, "line {}: second '{}' row", line, label)
+            }
+            RaceTableError::MissingRow(label) => write!(f, "no '{}' row", label),
+            RaceTableError::InvalidNumber { line, value } => {
+                write!(f, "line {}: invalid number '{}'", line, value)
+            }
+            RaceTableError::ColumnMismatch { times, distances } => write!(
+                f,
+                "{} times but {} distances, every race needs both",
+                times, distances
+            ),
+            RaceTableError::NoRaces => write!(f, "the table has no races"),
+            RaceTableError::Overflow { label } => {
+                write!(f, "a '{}' value doesn't fit in a u64", label)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RaceTableError {}
+
+const TIME: &str = "Time";
+const DISTANCE: &str = "Distance";
+
+/// The `Time:` and `Distance:` rows, found by label in any order. The numbers are kept as
+/// written until a `Kerning` says how to read them.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RaceTable {
+    times: Vec<String>,
+    distances: Vec<String>,
+}
+
+impl RaceTable {
+    pub fn parse(lines: &[&str]) -> Result<RaceTable, RaceTableError> {
+        let mut times = None;
+        let mut distances = None;
+
+        for (i, line) in lines.iter().enumerate() {
+            let line_num = i + 1;
+            let (label, values) = line
+                .split_once(':')
+                .ok_or(RaceTableError::MissingLabel { line: line_num })?;
+
+            let row = match label.trim() {
+                TIME => &mut times,
+                DISTANCE => &mut distances,
+                label => {
+                    return Err(RaceTableError::UnknownRow {
+                        line: line_num,
+                        label: label.to_string(),
+                    })
+                }
+            };
+            if row.is_some() {
+                return Err(RaceTableError::DuplicateRow {
+                    line: line_num,
+                    label: label.trim().to_string(),
+                });
+            }
+
+            let values = values
+                .split_whitespace()
+                .map(|v| {
+                    if v.bytes().all(|b| b.is_ascii_digit()) {
+                        Ok(v.to_string())
+                    } else {
+                        Err(RaceTableError::InvalidNumber {
+                            line: line_num,
+                            value: v.to_string(),
+                        })
+                    }
+                })
+                .collect::<Result<Vec<String>, RaceTableError>>()?;
+            *row = Some(values);
+        }
+
+        let times = times.ok_or(RaceTableError::MissingRow(TIME))?;
+        let distances = distances.ok_or(RaceTableError::MissingRow(DISTANCE))?;
+        if times.len() != distances.len() {
+            return Err(RaceTableError::ColumnMismatch {
+                times: times.len(),
+                distances: distances.len(),
+            });
+        }
+        if times.is_empty() {
+            return Err(RaceTableError::NoRaces);
+        }
+
+        Ok(RaceTable { times, distances })
+    }
+
+    pub fn races(&self, kerning: Kerning) -> Result<Vec<Race>, RaceTableError> {
+        let times = read_row(&self.times, kerning, TIME)?;
+        let records = read_row(&self.distances, kerning, DISTANCE)?;
+
+        Ok(times
+            .into_iter()
+            .zip(records)
+            .map(|(time, record)| Race { time, record })
+            .collect())
+    }
+}
+
+fn read_row(
+    values: &[String],
+    kerning: Kerning,
+    label: &'static str,
+) -> Result<Vec<u64>, RaceTableError> {
+    let parse = |v: &str| v.parse().map_err(|_| RaceTableError::Overflow { label });
+
+    match kerning {
+        Kerning::Columns => values.iter().map(|v| parse(v)).collect(),
+        Kerning::Kerned => Ok(vec![parse(&values.concat())?]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Kerning, Race, RaceTable, RaceTableError};
+
+    #[test]
+    fn reads_both_ways() {
+        // rows can come in either order
+        let table = RaceTable::parse(&["Distance:  9  40  200", "Time:      7  15   30"]).unwrap();
+
+        assert_eq!(
+            table.races(Kerning::Columns).unwrap(),
+            vec![
+                Race { time: 7, record: 9 },
+                Race {
+                    time: 15,
+                    record: 40
+                },
+                Race {
+                    time: 30,
+                    record: 200
+                },
+            ]
+        );
+        assert_eq!(
+            table.races(Kerning::Kerned).unwrap(),
+            vec![Race {
+                time: 71530,
+                record: 940200
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_tables() {
+        let parse = |lines: &[&str]| RaceTable::parse(lines).unwrap_err();
+
+        assert_eq!(
+            parse(&["Time: 1 2", "Distance: 3"]),
+            RaceTableError::ColumnMismatch {
+                times: 2,
+                distances: 1
+            }
+        );
+        assert_eq!(parse(&["Time: 1"]), RaceTableError::MissingRow("Distance"));
+        assert_eq!(
+            parse(&["Time: 1", "Speed: 2"]),
+            RaceTableError::UnknownRow {
+                line: 2,
+                label: "Speed".to_string()
+            }
+        );
+        assert_eq!(
+            parse(&["Time: 1", "Time: 2"]),
+            RaceTableError::DuplicateRow {
+                line: 2,
+                label: "Time".to_string()
+            }
+        );
+        assert_eq!(
+            parse(&["Time: 1", "Distance: -2"]),
+            RaceTableError::InvalidNumber {
+                line: 2,
+                value: "-2".to_string()
+            }
+        );
+        assert_eq!(
+            parse(&["Time 1", "Distance: 2"]),
+            RaceTableError::MissingLabel { line: 1 }
+        );
+        assert_eq!(parse(&["Time:", "Distance:"]), RaceTableError::NoRaces);
+
+        // each column fits, but not all of them run together
+        let table = RaceTable::parse(&["Time: 10000000000 10000000000", "Distance: 1 2"]).unwrap();
+        assert!(table.races(Kerning::Columns).is_ok());
+        assert_eq!(
+            table.races(Kerning::Kerned),
+            Err(RaceTableError::Overflow { label: "Time" })
+        );
+    }
+}
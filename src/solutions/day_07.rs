@@ -1,11 +1,22 @@
-use std::{cmp::Ordering, str::FromStr, collections::BinaryHeap};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+    fmt::Display,
+    str::FromStr,
+};
+
+use self::rules::{RuleSet, TieBreak};
 
 use super::Solver;
 
+pub mod explain;
+pub mod poker;
+pub mod rules;
+
 pub struct DaySevenSolver {}
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-enum Rank {
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum Rank {
     A,
     K,
     Q,
@@ -19,11 +30,27 @@ enum Rank {
     Four,
     Three,
     Two,
-    Wildcard,
 }
 
 impl Rank {
-    fn value(&self) -> u32 {
+    /// Strongest first, as in a standard deck.
+    pub const ALL: [Rank; 13] = [
+        Rank::A,
+        Rank::K,
+        Rank::Q,
+        Rank::J,
+        Rank::T,
+        Rank::Nine,
+        Rank::Eight,
+        Rank::Seven,
+        Rank::Six,
+        Rank::Five,
+        Rank::Four,
+        Rank::Three,
+        Rank::Two,
+    ];
+
+    pub fn value(&self) -> u32 {
         match self {
             Rank::A => 14,
             Rank::K => 13,
@@ -38,7 +65,6 @@ impl Rank {
             Rank::Four => 4,
             Rank::Three => 3,
             Rank::Two => 2,
-            Rank::Wildcard => 1,
         }
     }
 }
@@ -61,12 +87,33 @@ impl FromStr for Rank {
             "4" => Ok(Rank::Four),
             "3" => Ok(Rank::Three),
             "2" => Ok(Rank::Two),
-            "W" => Ok(Rank::Wildcard),
             _ => Err(()),
         }
     }
 }
 
+impl Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Rank::A => 'A',
+            Rank::K => 'K',
+            Rank::Q => 'Q',
+            Rank::J => 'J',
+            Rank::T => 'T',
+            Rank::Nine => '9',
+            Rank::Eight => '8',
+            Rank::Seven => '7',
+            Rank::Six => '6',
+            Rank::Five => '5',
+            Rank::Four => '4',
+            Rank::Three => '3',
+            Rank::Two => '2',
+        };
+
+        write!(f, "{}", symbol)
+    }
+}
+
 impl PartialOrd for Rank {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -80,7 +127,7 @@ impl Ord for Rank {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-enum HandType {
+pub enum HandType {
     FiveOfAKind,
     FourOfAKind,
     FullHouse,
@@ -138,100 +185,264 @@ impl Ord for HandType {
     }
 }
 
-impl From<Vec<Rank>> for HandType {
-    fn from(value: Vec<Rank>) -> Self {
-        let mut ranks = value;
-        ranks.sort();
+impl HandType {
+    /// The type of a hand from how many cards of each rank it has, biggest group first.
+    /// Groups of more than five still only count as five of a kind.
+    fn from_groups(groups: &[usize]) -> HandType {
+        let first = groups.first().copied().unwrap_or(0);
+        let second = groups.get(1).copied().unwrap_or(0);
+
+        match (first, second) {
+            (5.., _) => HandType::FiveOfAKind,
+            (4, _) => HandType::FourOfAKind,
+            (3, 2..) => HandType::FullHouse,
+            (3, _) => HandType::ThreeOfAKind,
+            (2, 2) => HandType::TwoPair,
+            (2, _) => HandType::OnePair,
+            _ => HandType::HighCard,
+        }
+    }
 
-        let total = ranks.len();
-        let ranks = ranks.iter().filter(|r| r != &&Rank::Wildcard).collect::<Vec<_>>();
-        let wildcards = total - ranks.len();
+    /// The best type the hand can make with its wild cards. Every wild card joins the biggest
+    /// group of a non-wild rank: that makes the biggest group as big as possible, which is
+    /// what matters most, and the second biggest group is no smaller than it would be
+    /// otherwise. `classify_exhaustive` gives the same answer the slow way.
+    pub fn classify(cards: &[Rank], rules: &RuleSet) -> HandType {
+        let (mut groups, wildcards) = rank_groups(cards, rules);
 
-        let mut counts = [0; 15];
-        for rank in ranks {
-            counts[rank.value() as usize] += 1;
+        match groups.first_mut() {
+            Some(biggest) => *biggest += wildcards,
+            None => groups.push(wildcards),
         }
 
-        let t = if counts.iter().any(|&c| c == 5) {
-            // can't get any higher than 5 of a kind
-            return HandType::FiveOfAKind
-        } else if counts.iter().any(|&c| c == 4) {
-            HandType::FourOfAKind
-        } else if counts.iter().any(|&c| c == 3) && counts.iter().any(|&c| c == 2) {
-            HandType::FullHouse
-        } else if counts.iter().any(|&c| c == 3) {
-            HandType::ThreeOfAKind
-        } else if counts.iter().filter(|&&c| c == 2).count() == 2 {
-            HandType::TwoPair
-        } else if counts.iter().any(|&c| c == 2) {
-            HandType::OnePair
-        } else {
-            HandType::HighCard
-        };
+        HandType::from_groups(&groups)
+    }
 
-        match wildcards {
-            5 => {
-                HandType::FiveOfAKind
-            },
-            4 => {
-                HandType::FiveOfAKind
-            },
-            3 => {
-                match t {
-                    HandType::OnePair => HandType::FiveOfAKind,
-                    _ => HandType::FourOfAKind
-                }
-            },
-            2 => {
-                match t {
-                    HandType::ThreeOfAKind => HandType::FiveOfAKind,
-                    HandType::OnePair => HandType::FourOfAKind,
-                    _ => HandType::ThreeOfAKind
-                }
-            },
-            1 => {
-                match t {
-                    HandType::FourOfAKind => HandType::FiveOfAKind,
-                    HandType::ThreeOfAKind => HandType::FourOfAKind,
-                    HandType::TwoPair => HandType::FullHouse,
-                    HandType::OnePair => HandType::ThreeOfAKind,
-                    _ => HandType::OnePair
-                }
-            },
-            _ => {
-                t
+    /// The best type the hand can make, found by trying every way of standing its wild cards
+    /// in for ranks in play. Only practical for a few wild cards.
+    pub fn classify_exhaustive(cards: &[Rank], rules: &RuleSet) -> HandType {
+        let (_, wildcards) = rank_groups(cards, rules);
+        let fixed: Vec<&Rank> = cards.iter().filter(|c| !rules.is_wild(c)).collect();
+        let stand_ins: Vec<&Rank> = rules.order.iter().filter(|r| !rules.is_wild(r)).collect();
+
+        if stand_ins.is_empty() {
+            // nothing for the wild cards to become, so they can only match each other
+            return HandType::from_groups(&[wildcards]);
+        }
+
+        // which ranks the wild cards become matters, not which wild card becomes which, so
+        // only try non-decreasing choices into `stand_ins`
+        fn best(
+            hand: &mut Vec<Rank>,
+            stand_ins: &[&Rank],
+            from: usize,
+            remaining: usize,
+        ) -> HandType {
+            if remaining == 0 {
+                return HandType::classify(hand, &RuleSet::default());
             }
+
+            (from..stand_ins.len())
+                .map(|i| {
+                    hand.push(stand_ins[i].clone());
+                    let t = best(hand, stand_ins, i, remaining - 1);
+                    hand.pop();
+                    t
+                })
+                .max()
+                .unwrap()
         }
+
+        let mut hand: Vec<Rank> = fixed.into_iter().cloned().collect();
+        best(&mut hand, &stand_ins, 0, wildcards)
     }
 }
 
+// sizes of the groups of each non-wild rank, biggest first, and how many wild cards there are
+fn rank_groups(cards: &[Rank], rules: &RuleSet) -> (Vec<usize>, usize) {
+    let mut counts: HashMap<&Rank, usize> = HashMap::new();
+    for card in cards.iter().filter(|c| !rules.is_wild(c)) {
+        *counts.entry(card).or_default() += 1;
+    }
+    let wildcards = cards.len() - counts.values().sum::<usize>();
+
+    let mut groups: Vec<usize> = counts.into_values().collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
 
-#[derive(Debug, PartialEq, Eq)]
-struct Hand {
-    bet: u64,
-    cards: Vec<Rank>,
+    (groups, wildcards)
 }
 
-impl FromStr for Hand {
-    type Err = ();
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum HandErrorKind {
+    UnknownCard(char),
+    /// a real card, but one the rules leave out
+    CardNotInPlay(Rank),
+    WrongLength {
+        expected: usize,
+        found: usize,
+    },
+    TooManyCards(usize),
+    MissingCards,
+    MissingBet,
+    InvalidBet(String),
+    UnexpectedToken(String),
+    DuplicateHand {
+        first_line: usize,
+    },
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+/// A hand that couldn't be read. `line` is only known when parsing a whole input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HandError {
+    pub line: Option<usize>,
+    pub kind: HandErrorKind,
+}
+
+impl From<HandErrorKind> for HandError {
+    fn from(kind: HandErrorKind) -> Self {
+        HandError { line: None, kind }
+    }
+}
+
+impl Display for HandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+
+        match &self.kind {
+            HandErrorKind::UnknownCard(c) => write!(f, "unknown card '{}'", c),
+            HandErrorKind::CardNotInPlay(r) => write!(f, "'{}' isn't played under these rules", r),
+            HandErrorKind::WrongLength { expected, found } => {
+                write!(f, "expected {} cards, found {}", expected, found)
+            }
+            HandErrorKind::TooManyCards(n) => {
+                write!(f, "hands of {} cards are too big to rank", n)
+            }
+            HandErrorKind::MissingCards => write!(f, "expected a hand of cards"),
+            HandErrorKind::MissingBet => write!(f, "expected a bet after the cards"),
+            HandErrorKind::InvalidBet(b) => write!(f, "invalid bet '{}'", b),
+            HandErrorKind::UnexpectedToken(t) => write!(f, "unexpected '{}' after the bet", t),
+            HandErrorKind::DuplicateHand { first_line } => {
+                write!(f, "same cards as line {}", first_line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HandError {}
+
+/// The most cards a hand can have and still fit its strength into a `u64`.
+pub const MAX_HAND_SIZE: usize = 15;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Hand {
+    pub bet: u64,
+    pub cards: Vec<Rank>,
+    hand_type: HandType,
+    strength: u64,
+}
+
+impl Hand {
+    pub fn new(cards: Vec<Rank>, bet: u64, rules: &RuleSet) -> Result<Hand, HandError> {
+        if cards.len() != rules.hand_size {
+            return Err(HandErrorKind::WrongLength {
+                expected: rules.hand_size,
+                found: cards.len(),
+            }
+            .into());
+        }
+        if cards.len() > MAX_HAND_SIZE {
+            return Err(HandErrorKind::TooManyCards(cards.len()).into());
+        }
+        if let Some(card) = cards.iter().find(|c| rules.strength(c).is_none()) {
+            return Err(HandErrorKind::CardNotInPlay(card.clone()).into());
+        }
+
+        let tie_break: Vec<u32> = tie_break_order(&cards, rules)
+            .unwrap()
+            .into_iter()
+            .map(|(_, strength)| strength)
+            .collect();
+
+        let hand_type = HandType::classify(&cards, rules);
+        let strength = pack_strength(&hand_type, &tie_break);
+
+        Ok(Hand {
+            bet,
+            cards,
+            hand_type,
+            strength,
+        })
+    }
+
+    /// Reads `<cards> <bet>`, e.g. `32T3K 765`.
+    pub fn parse(s: &str, rules: &RuleSet) -> Result<Hand, HandError> {
         let mut split = s.trim().split_ascii_whitespace();
-        
+
         let cards: Vec<Rank> = split
             .next()
-            .expect("should be two parts...")
+            .ok_or(HandErrorKind::MissingCards)?
             .chars()
-            .map(|s| {
-                s.to_string().parse()
+            .map(|c| {
+                c.to_string()
+                    .parse()
+                    .map_err(|_| HandErrorKind::UnknownCard(c))
             })
-            .collect::<Result<Vec<Rank>, ()>>()
-            .unwrap_or_default();
+            .collect::<Result<Vec<Rank>, HandErrorKind>>()?;
+
+        let bet = split.next().ok_or(HandErrorKind::MissingBet)?;
+        let bet = bet
+            .parse()
+            .map_err(|_| HandErrorKind::InvalidBet(bet.to_string()))?;
+
+        if let Some(extra) = split.next() {
+            return Err(HandErrorKind::UnexpectedToken(extra.to_string()).into());
+        }
+
+        Hand::new(cards, bet, rules)
+    }
+
+    pub fn hand_type(&self) -> &HandType {
+        &self.hand_type
+    }
 
-        let bet = split.next().unwrap().parse().unwrap();
+    /// Orders hands the same way the rules do, so ranking them is a plain sort on this.
+    pub fn strength(&self) -> u64 {
+        self.strength
+    }
+}
 
-        Ok(Hand { bet, cards })
+// each card with its strength, in the order the rules compare them, or `None` if a card
+// isn't in play
+fn tie_break_order<'c>(cards: &'c [Rank], rules: &RuleSet) -> Option<Vec<(&'c Rank, u32)>> {
+    let mut order = cards
+        .iter()
+        .map(|c| Some((c, rules.strength(c)?)))
+        .collect::<Option<Vec<(&Rank, u32)>>>()?;
+    if rules.tie_break == TieBreak::HighestFirst {
+        order.sort_by_key(|&(_, strength)| Reverse(strength));
     }
+
+    Some(order)
+}
+
+fn pack_strength(hand_type: &HandType, tie_break: &[u32]) -> u64 {
+    pack(
+        usize::try_from(hand_type.clone()).unwrap() as u64,
+        tie_break,
+    )
+}
+
+// the category in the top 4 bits, then 4 bits per card strength in tie-break order, first
+// card highest; strengths never pass 13 so each fits in a nibble
+fn pack(category: u64, tie_break: &[u32]) -> u64 {
+    tie_break
+        .iter()
+        .enumerate()
+        .fold(category << (4 * MAX_HAND_SIZE), |key, (i, &s)| {
+            key | (s as u64) << (4 * (MAX_HAND_SIZE - 1 - i))
+        })
 }
 
 impl PartialOrd for Hand {
@@ -242,53 +453,124 @@ impl PartialOrd for Hand {
 
 impl Ord for Hand {
     fn cmp(&self, other: &Self) -> Ordering {
-        let self_type = HandType::from(self.cards.clone());
-        let other_type = HandType::from(other.cards.clone());
+        self.strength.cmp(&other.strength)
+    }
+}
 
-        if self_type != other_type {
-            self_type.cmp(&other_type)
-        } else {
-            self.cards.cmp(&other.cards)
+/// Parses one hand per line. The same cards can't be dealt twice, since two equal hands
+/// would have no defined order.
+pub fn parse_hands(input: &[&str], rules: &RuleSet) -> Result<Vec<Hand>, HandError> {
+    let mut seen: HashMap<&[Rank], usize> = HashMap::new();
+    let mut hands = Vec::with_capacity(input.len());
+
+    for (i, s) in input.iter().enumerate() {
+        let line = i + 1;
+        let hand = Hand::parse(s, rules).map_err(|e| HandError {
+            line: Some(line),
+            ..e
+        })?;
+        hands.push(hand);
+    }
+
+    for (i, hand) in hands.iter().enumerate() {
+        if let Some(&first_line) = seen.get(hand.cards.as_slice()) {
+            return Err(HandError {
+                line: Some(i + 1),
+                kind: HandErrorKind::DuplicateHand { first_line },
+            });
         }
+        seen.insert(&hand.cards, i + 1);
     }
+
+    Ok(hands)
+}
+
+/// Sorts hands weakest first, so a hand's rank is its index plus one.
+pub fn rank_hands(mut hands: Vec<Hand>) -> Vec<Hand> {
+    hands.sort_unstable_by_key(Hand::strength);
+    hands
+}
+
+/// The sum of every hand's bet times its rank, weakest hand first.
+pub fn total_winnings(input: &[&str], rules: &RuleSet) -> Result<u64, HandError> {
+    let hands = rank_hands(parse_hands(input, rules)?);
+
+    let res = hands
+        .iter()
+        .enumerate()
+        .fold(0, |acc, c| acc + (c.0 + 1) as u64 * c.1.bet);
+
+    Ok(res)
 }
 
 impl<'a> Solver<'a> for DaySevenSolver {
     fn part_1(&self, input: &'a [&'a str]) -> Result<String, ()> {
-        let hands: Vec<Hand> = input.iter()
-            .map(|s| s.parse().unwrap())
-            .collect::<BinaryHeap<Hand>>()
-            .into_sorted_vec();
-
-        let res = hands.iter().enumerate()
-            .fold(0, |acc, c| {
-                acc + (c.0 + 1) as u64 * c.1.bet
-            });
+        let res = total_winnings(input, &RuleSet::default()).map_err(|_| ())?;
 
         Ok(res.to_string())
     }
 
     fn part_2(&self, input: &'a [&'a str]) -> Result<String, ()> {
-        let hands: Vec<Hand> = input.iter()
-            .map(|s| {
-                let s = s.replace('J',  "W");
-                s.parse().unwrap()
-            })
-            .collect::<BinaryHeap<Hand>>()
-            .into_sorted_vec();
-
-        let res = hands.iter().enumerate()
-            .fold(0, |acc, c| {
-                acc + (c.0 + 1) as u64 * c.1.bet
-            });
+        let res = total_winnings(input, &RuleSet::jokers()).map_err(|_| ())?;
 
         Ok(res.to_string())
     }
+
+    fn run_mode(&self, mode: &str, args: &[&str], input: &'a [&'a str]) -> Result<String, ()> {
+        // `jokers` anywhere in the args switches to part 2's rules
+        let rules = if args.contains(&"jokers") {
+            RuleSet::jokers()
+        } else {
+            RuleSet::default()
+        };
+
+        match mode {
+            "explain" => {
+                let hands = rank_hands(parse_hands(input, &rules).map_err(|_| ())?);
+
+                Ok(explain::ranking_table(&hands))
+            }
+            // `compare <cards> <cards>`, e.g. `compare KK677 KTJJT jokers`
+            "compare" => {
+                let hand = |cards: &&str| Hand::parse(&format!("{} 0", cards), &rules);
+                let hands = args
+                    .iter()
+                    .filter(|&&a| a != "jokers")
+                    .map(hand)
+                    .collect::<Result<Vec<Hand>, HandError>>()
+                    .map_err(|_| ())?;
+                let [a, b] = hands.as_slice() else {
+                    return Err(());
+                };
+
+                Ok(explain::comparison(a, b, &rules))
+            }
+            // `poker <cards...>`, e.g. `poker Ah Kh 7c 7d Qh Jh Th`
+            "poker" => {
+                let cards = args
+                    .iter()
+                    .map(|c| c.parse())
+                    .collect::<Result<Vec<poker::Card>, poker::PokerError>>()
+                    .map_err(|_| ())?;
+                let best = poker::best_hand(&cards).map_err(|_| ())?;
+
+                Ok(best.to_string())
+            }
+            _ => Err(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solutions::{day_07::{DaySevenSolver}, normalize_input, Solver};
+    use crate::solutions::{
+        day_07::{
+            parse_hands,
+            rules::{RuleSet, TieBreak},
+            total_winnings, DaySevenSolver, Hand, HandError, HandErrorKind, HandType, Rank,
+        },
+        normalize_input, Solver,
+    };
 
     const INPUT: &str = r#"
         32T3K 765
@@ -301,7 +583,7 @@ mod tests {
     #[test]
     fn part_1_example() {
         let solver = DaySevenSolver {};
-        let input = normalize_input(&INPUT).unwrap();
+        let input = normalize_input(INPUT).unwrap();
 
         let res = solver.part_1(&input).unwrap();
         assert_eq!(res, "6440");
@@ -310,9 +592,184 @@ mod tests {
     #[test]
     fn part_2_example() {
         let solver = DaySevenSolver {};
-        let input = normalize_input(&INPUT).unwrap();
+        let input = normalize_input(INPUT).unwrap();
 
         let res = solver.part_2(&input).unwrap();
         assert_eq!(res, "5905");
     }
+
+    #[test]
+    fn rule_set_variants() {
+        let hand_type =
+            |s: &str, rules: &RuleSet| Hand::parse(s, rules).unwrap().hand_type().clone();
+
+        // twos are wild as well as jacks
+        let mut rules = RuleSet::jokers();
+        rules.wild.push(Rank::Two);
+        assert_eq!(hand_type("2J3K4 1", &rules), HandType::ThreeOfAKind);
+        assert_eq!(hand_type("2J22J 1", &rules), HandType::FiveOfAKind);
+
+        // three card hands
+        let rules = RuleSet {
+            hand_size: 3,
+            ..RuleSet::jokers()
+        };
+        assert_eq!(hand_type("KJK 1", &rules), HandType::ThreeOfAKind);
+        assert_eq!(hand_type("K2J 1", &rules), HandType::OnePair);
+        assert!(Hand::parse("KJKK 1", &rules).is_err());
+
+        // a rank left out of the order isn't in play at all
+        let mut rules = RuleSet::default();
+        rules.order.retain(|r| r != &Rank::Two);
+        assert!(Hand::parse("2KKKK 1", &rules).is_err());
+    }
+
+    #[test]
+    fn tie_breaks() {
+        let input = ["2AAAA 1", "33332 2"];
+
+        // card by card the leading 3 wins, by highest card the aces do
+        assert_eq!(total_winnings(&input, &RuleSet::default()), Ok(1 + 2 * 2));
+        let rules = RuleSet {
+            tie_break: TieBreak::HighestFirst,
+            ..RuleSet::default()
+        };
+        assert_eq!(total_winnings(&input, &rules), Ok(2 + 2));
+    }
+
+    #[test]
+    fn strength_orders_like_type_then_cards() {
+        let rules = RuleSet::default();
+        let strength = |s: &str| Hand::parse(s, &rules).unwrap().strength();
+
+        assert!(strength("22223 1") > strength("AAKKQ 1"));
+        assert!(strength("AAKKQ 1") > strength("AAKKJ 1"));
+        assert!(strength("32T3K 1") < strength("KK677 1"));
+        assert_eq!(strength("AAAAA 1") >> 60, 6);
+        assert_eq!(strength("32T3K 1") & 0xf_ffff_ffff, 0);
+    }
+
+    // every hand of `size` cards drawn from `ranks`
+    fn all_hands(ranks: &[Rank], size: usize) -> Vec<Vec<Rank>> {
+        (0..size).fold(vec![vec![]], |hands, _| {
+            hands
+                .iter()
+                .flat_map(|hand| {
+                    ranks.iter().map(move |r| {
+                        let mut hand = hand.clone();
+                        hand.push(r.clone());
+                        hand
+                    })
+                })
+                .collect()
+        })
+    }
+
+    // replaces each wild card, one at a time, by every rank in `ranks`
+    fn brute_force(cards: &[Rank], ranks: &[Rank], rules: &RuleSet) -> HandType {
+        match cards.iter().position(|c| rules.is_wild(c)) {
+            None => HandType::classify(cards, &RuleSet::default()),
+            Some(i) => ranks
+                .iter()
+                .filter(|r| !rules.is_wild(r))
+                .map(|r| {
+                    let mut cards = cards.to_vec();
+                    cards[i] = r.clone();
+                    brute_force(&cards, ranks, rules)
+                })
+                .max()
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn wildcards_match_brute_force() {
+        let ranks = [Rank::J, Rank::Two, Rank::Three, Rank::K];
+        // wild cards may also become a rank that isn't dealt; any one stands in for the rest
+        let substitutes = [
+            Rank::J,
+            Rank::Two,
+            Rank::Three,
+            Rank::Four,
+            Rank::K,
+            Rank::A,
+        ];
+
+        let mut two_wild = RuleSet::jokers();
+        two_wild.wild.push(Rank::Two);
+
+        for rules in [RuleSet::default(), RuleSet::jokers(), two_wild] {
+            for size in 0..=5 {
+                for hand in all_hands(&ranks, size) {
+                    let expected = brute_force(&hand, &substitutes, &rules);
+
+                    assert_eq!(HandType::classify(&hand, &rules), expected, "{:?}", hand);
+                    assert_eq!(
+                        HandType::classify_exhaustive(&hand, &rules),
+                        expected,
+                        "{:?}",
+                        hand
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_rank_wild() {
+        let rules = RuleSet {
+            wild: Rank::ALL.to_vec(),
+            ..RuleSet::default()
+        };
+        let cards = [Rank::A, Rank::Two, Rank::Three];
+
+        assert_eq!(HandType::classify(&cards, &rules), HandType::ThreeOfAKind);
+        assert_eq!(
+            HandType::classify_exhaustive(&cards, &rules),
+            HandType::ThreeOfAKind
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_hands() {
+        let rules = RuleSet::default();
+        let error = |lines: &[&str]| parse_hands(lines, &rules).unwrap_err();
+        let at = |line, kind| HandError {
+            line: Some(line),
+            kind,
+        };
+
+        assert_eq!(
+            error(&["32T3K 765", "32X3K 1"]),
+            at(2, HandErrorKind::UnknownCard('X'))
+        );
+        assert_eq!(
+            error(&["32T3 765"]),
+            at(
+                1,
+                HandErrorKind::WrongLength {
+                    expected: 5,
+                    found: 4
+                }
+            )
+        );
+        assert_eq!(error(&["32T3K"]), at(1, HandErrorKind::MissingBet));
+        assert_eq!(
+            error(&["32T3K 7x"]),
+            at(1, HandErrorKind::InvalidBet("7x".to_string()))
+        );
+        assert_eq!(
+            error(&["32T3K 7 8"]),
+            at(1, HandErrorKind::UnexpectedToken("8".to_string()))
+        );
+        assert_eq!(
+            error(&["32T3K 1", "KK677 2", "32T3K 3"]),
+            at(3, HandErrorKind::DuplicateHand { first_line: 1 })
+        );
+        assert_eq!(
+            error(&["32T3K 1", "KK677 2", "32T3K 3"]).to_string(),
+            "line 3: same cards as line 1"
+        );
+        assert_eq!(DaySevenSolver {}.part_1(&["32T3K"]), Err(()));
+    }
 }
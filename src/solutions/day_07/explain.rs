@@ -0,0 +1,189 @@
+use std::{cmp::Ordering, fmt::Write};
+
+use super::{rules::RuleSet, tie_break_order, Hand, HandType, Rank};
+
+/// One comparison made while deciding which of two hands is stronger.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Step {
+    Types(HandType, HandType),
+    /// the cards compared at `position` (0-based, in tie-break order) and their strengths
+    Cards {
+        position: usize,
+        cards: (Rank, Rank),
+        strengths: (u32, u32),
+    },
+}
+
+impl Hand {
+    /// The hand's type if its wild cards were only worth their face value.
+    pub fn natural_type(&self) -> HandType {
+        HandType::classify(&self.cards, &RuleSet::default())
+    }
+
+    /// How the rules decide between this hand and `other`: the types, then cards until one
+    /// differs. The last step is the deciding one, unless the hands are equal.
+    pub fn compare_steps(&self, other: &Hand, rules: &RuleSet) -> Vec<Step> {
+        let mut steps = vec![Step::Types(
+            self.hand_type().clone(),
+            other.hand_type().clone(),
+        )];
+        if self.hand_type() != other.hand_type() {
+            return steps;
+        }
+
+        let ours = tie_break_order(&self.cards, rules).unwrap_or_default();
+        let theirs = tie_break_order(&other.cards, rules).unwrap_or_default();
+        for (position, (a, b)) in ours.iter().zip(&theirs).enumerate() {
+            steps.push(Step::Cards {
+                position,
+                cards: (a.0.clone(), b.0.clone()),
+                strengths: (a.1, b.1),
+            });
+            if a.1 != b.1 {
+                break;
+            }
+        }
+
+        steps
+    }
+}
+
+fn cards(hand: &Hand) -> String {
+    hand.cards.iter().map(|c| c.to_string()).collect()
+}
+
+/// Every hand weakest first, as ranked by `rank_hands`, with what it contributes to the total.
+pub fn ranking_table(ranked: &[Hand]) -> String {
+    let mut out = String::from("rank | cards | natural type | type | bet | winnings\n");
+    for (i, hand) in ranked.iter().enumerate() {
+        let rank = i as u64 + 1;
+
+        // writing to a String can't fail
+        writeln!(
+            out,
+            "{:>4} | {} | {:?} | {:?} | {} | {}",
+            rank,
+            cards(hand),
+            hand.natural_type(),
+            hand.hand_type(),
+            hand.bet,
+            rank * hand.bet
+        )
+        .unwrap();
+    }
+    let total: u64 = ranked
+        .iter()
+        .enumerate()
+        .map(|(i, hand)| (i as u64 + 1) * hand.bet)
+        .sum();
+    writeln!(out, "total winnings: {}", total).unwrap();
+
+    out
+}
+
+/// `compare_steps` written out, one line per step.
+pub fn comparison(a: &Hand, b: &Hand, rules: &RuleSet) -> String {
+    let (a_cards, b_cards) = (cards(a), cards(b));
+
+    let mut out = String::new();
+    for step in a.compare_steps(b, rules) {
+        match step {
+            Step::Types(x, y) => {
+                writeln!(out, "types: {} is {:?}, {} is {:?}", a_cards, x, b_cards, y)
+            }
+            Step::Cards {
+                position,
+                cards: (x, y),
+                strengths: (sx, sy),
+            } => writeln!(
+                out,
+                "card {}: {} ({}) vs {} ({})",
+                position + 1,
+                x,
+                sx,
+                y,
+                sy
+            ),
+        }
+        .unwrap();
+    }
+
+    let verdict = match a.cmp(b) {
+        Ordering::Greater => format!("{} is stronger", a_cards),
+        Ordering::Less => format!("{} is stronger", b_cards),
+        Ordering::Equal => "the hands are tied".to_string(),
+    };
+    writeln!(out, "{}", verdict).unwrap();
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solutions::{
+        day_07::{rules::RuleSet, DaySevenSolver, Hand, HandType, Rank},
+        normalize_input, Solver,
+    };
+
+    use super::Step;
+
+    #[test]
+    fn compares_step_by_step() {
+        let rules = RuleSet::jokers();
+        let hand = |s: &str| Hand::parse(s, &rules).unwrap();
+        let (a, b) = (hand("KTJJT 220"), hand("QQQJA 483"));
+
+        assert_eq!(a.natural_type(), HandType::TwoPair);
+        assert_eq!(a.hand_type(), &HandType::FourOfAKind);
+        assert_eq!(
+            a.compare_steps(&b, &rules),
+            vec![
+                Step::Types(HandType::FourOfAKind, HandType::FourOfAKind),
+                Step::Cards {
+                    position: 0,
+                    cards: (Rank::K, Rank::Q),
+                    strengths: (12, 11)
+                },
+            ]
+        );
+        assert_eq!(
+            a.compare_steps(&hand("32T3K 1"), &rules),
+            vec![Step::Types(HandType::FourOfAKind, HandType::OnePair)]
+        );
+    }
+
+    #[test]
+    fn explain_modes() {
+        let input = normalize_input(
+            "
+            32T3K 765
+            T55J5 684
+            KK677 28
+            KTJJT 220
+            QQQJA 483
+            ",
+        )
+        .unwrap();
+        let solver = DaySevenSolver {};
+
+        let table = solver.run_mode("explain", &["jokers"], &input).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[1], "   1 | 32T3K | OnePair | OnePair | 765 | 765");
+        assert_eq!(
+            lines[5],
+            "   5 | KTJJT | TwoPair | FourOfAKind | 220 | 1100"
+        );
+        assert_eq!(lines[6], "total winnings: 5905");
+
+        let comparison = solver
+            .run_mode("compare", &["KK677", "KTJJT"], &input)
+            .unwrap();
+        assert_eq!(
+            comparison,
+            "types: KK677 is TwoPair, KTJJT is TwoPair\n\
+             card 1: K (12) vs K (12)\n\
+             card 2: K (12) vs T (9)\n\
+             KK677 is stronger\n"
+        );
+    }
+}
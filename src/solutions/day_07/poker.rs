@@ -0,0 +1,302 @@
+use std::{cmp::Reverse, collections::HashSet, fmt::Display, str::FromStr};
+
+use super::{pack, rules::RuleSet, HandType, Rank};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Suit::Clubs => 'c',
+            Suit::Diamonds => 'd',
+            Suit::Hearts => 'h',
+            Suit::Spades => 's',
+        };
+
+        write!(f, "{}", symbol)
+    }
+}
+
+/// A card as written in a poker hand, like `Ah` or `7c`. Camel Cards have no suits, so a bare
+/// rank like `7` is a card too; it just never counts towards a flush.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Option<Suit>,
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rank)?;
+        if let Some(suit) = self.suit {
+            write!(f, "{}", suit)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PokerError {
+    InvalidCard(String),
+    /// the same rank and suit dealt twice
+    DuplicateCard(Card),
+    WrongCount(usize),
+}
+
+impl Display for PokerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PokerError::InvalidCard(s) => write!(f, "invalid card '{}'", s),
+            PokerError::DuplicateCard(c) => write!(f, "{} is dealt twice", c),
+            PokerError::WrongCount(n) => write!(f, "can't make a poker hand from {} cards", n),
+        }
+    }
+}
+
+impl std::error::Error for PokerError {}
+
+impl FromStr for Card {
+    type Err = PokerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PokerError::InvalidCard(s.to_string());
+
+        let mut chars = s.chars();
+        let rank = chars
+            .next()
+            .and_then(|c| c.to_string().parse().ok())
+            .ok_or_else(invalid)?;
+        let suit = match chars.next() {
+            None => None,
+            Some('c') => Some(Suit::Clubs),
+            Some('d') => Some(Suit::Diamonds),
+            Some('h') => Some(Suit::Hearts),
+            Some('s') => Some(Suit::Spades),
+            Some(_) => return Err(invalid()),
+        };
+        if chars.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(Card { rank, suit })
+    }
+}
+
+/// Weakest first. Five of a kind only comes up without suits, or with repeated cards.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum PokerCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    FiveOfAKind,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PokerHand {
+    pub cards: Vec<Card>,
+    category: PokerCategory,
+    strength: u64,
+}
+
+impl PokerHand {
+    pub fn category(&self) -> PokerCategory {
+        self.category
+    }
+
+    /// Orders hands like `Hand::strength` does: category first, then the cards that decide
+    /// ties within it.
+    pub fn strength(&self) -> u64 {
+        self.strength
+    }
+}
+
+impl Display for PokerHand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cards: Vec<String> = self.cards.iter().map(|c| c.to_string()).collect();
+        write!(f, "{:?}: {}", self.category, cards.join(" "))
+    }
+}
+
+/// Ranks a five card hand with aces high, except in the 5-4-3-2-A straight.
+pub fn evaluate(cards: &[Card]) -> Result<PokerHand, PokerError> {
+    if cards.len() != 5 {
+        return Err(PokerError::WrongCount(cards.len()));
+    }
+    check_unique(cards)?;
+
+    let rules = RuleSet::default();
+    let ranks: Vec<Rank> = cards.iter().map(|c| c.rank.clone()).collect();
+    let strength = |r: &Rank| rules.strength(r).unwrap();
+
+    // biggest groups first, then higher ranks, which is how ties are broken for everything
+    // but straights
+    let mut tie_break: Vec<u32> = ranks.iter().map(strength).collect();
+    let count = |s: u32| tie_break.iter().filter(|&&t| t == s).count();
+    let mut order: Vec<(usize, u32)> = tie_break.iter().map(|&s| (count(s), s)).collect();
+    order.sort_by_key(|&key| Reverse(key));
+    tie_break = order.into_iter().map(|(_, s)| s).collect();
+
+    let flush = cards[0].suit.is_some() && cards.iter().all(|c| c.suit == cards[0].suit);
+    let straight_high = straight_high(&tie_break);
+
+    let category = match (HandType::classify(&ranks, &rules), straight_high, flush) {
+        (HandType::FiveOfAKind, _, _) => PokerCategory::FiveOfAKind,
+        (_, Some(_), true) => PokerCategory::StraightFlush,
+        (HandType::FourOfAKind, _, _) => PokerCategory::FourOfAKind,
+        (HandType::FullHouse, _, _) => PokerCategory::FullHouse,
+        (_, _, true) => PokerCategory::Flush,
+        (_, Some(_), _) => PokerCategory::Straight,
+        (HandType::ThreeOfAKind, _, _) => PokerCategory::ThreeOfAKind,
+        (HandType::TwoPair, _, _) => PokerCategory::TwoPair,
+        (HandType::OnePair, _, _) => PokerCategory::OnePair,
+        (HandType::HighCard, _, _) => PokerCategory::HighCard,
+    };
+    if let (Some(high), PokerCategory::Straight | PokerCategory::StraightFlush) =
+        (straight_high, category)
+    {
+        tie_break = vec![high];
+    }
+
+    Ok(PokerHand {
+        cards: cards.to_vec(),
+        category,
+        strength: pack(category as u64, &tie_break),
+    })
+}
+
+// the strength of the top card if these five distinct strengths, highest first, make a
+// straight; the ace plays low in 5-4-3-2-A, which makes it a five high straight
+fn straight_high(strengths: &[u32]) -> Option<u32> {
+    let ace = RuleSet::default().strength(&Rank::A).unwrap();
+    let five = RuleSet::default().strength(&Rank::Five).unwrap();
+
+    if strengths.windows(2).all(|w| w[0] == w[1] + 1) {
+        Some(strengths[0])
+    } else if strengths[0] == ace
+        && strengths[1] == five
+        && strengths[1..].windows(2).all(|w| w[0] == w[1] + 1)
+    {
+        Some(five)
+    } else {
+        None
+    }
+}
+
+fn check_unique(cards: &[Card]) -> Result<(), PokerError> {
+    let mut seen = HashSet::new();
+    for card in cards.iter().filter(|c| c.suit.is_some()) {
+        if !seen.insert(card) {
+            return Err(PokerError::DuplicateCard(card.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+/// The strongest five card hand among `cards`, which can be five to seven cards as in
+/// Texas hold 'em.
+pub fn best_hand(cards: &[Card]) -> Result<PokerHand, PokerError> {
+    if !(5..=7).contains(&cards.len()) {
+        return Err(PokerError::WrongCount(cards.len()));
+    }
+    check_unique(cards)?;
+
+    // leave out every pair of cards (or one, or none) in turn
+    let n = cards.len();
+    let mut best: Option<PokerHand> = None;
+    for left_out in 0_u32..(1 << n) {
+        if n - left_out.count_ones() as usize != 5 {
+            continue;
+        }
+
+        let five: Vec<Card> = (0..n)
+            .filter(|i| left_out & (1 << i) == 0)
+            .map(|i| cards[i].clone())
+            .collect();
+        let hand = evaluate(&five)?;
+        if best.as_ref().is_none_or(|b| hand.strength > b.strength) {
+            best = Some(hand);
+        }
+    }
+
+    Ok(best.unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_hand, evaluate, Card, PokerCategory, PokerError, PokerHand};
+
+    fn cards(s: &str) -> Vec<Card> {
+        s.split_whitespace().map(|c| c.parse().unwrap()).collect()
+    }
+
+    fn hand(s: &str) -> PokerHand {
+        evaluate(&cards(s)).unwrap()
+    }
+
+    #[test]
+    fn categorizes() {
+        let category = |s: &str| hand(s).category();
+
+        assert_eq!(category("Ah Kh Qh Jh Th"), PokerCategory::StraightFlush);
+        assert_eq!(category("5d 4d 3d 2d Ad"), PokerCategory::StraightFlush);
+        assert_eq!(category("9c 9d 9h 9s 2c"), PokerCategory::FourOfAKind);
+        assert_eq!(category("9c 9d 9h 2s 2c"), PokerCategory::FullHouse);
+        assert_eq!(category("Kh 9h 7h 4h 2h"), PokerCategory::Flush);
+        assert_eq!(category("6c 5d 4h 3s 2c"), PokerCategory::Straight);
+        assert_eq!(category("Ac 2d 3h 4s 5c"), PokerCategory::Straight);
+        assert_eq!(category("Qc Kd Ah 2s 3c"), PokerCategory::HighCard);
+        assert_eq!(category("9c 9d 9h Ks 2c"), PokerCategory::ThreeOfAKind);
+        assert_eq!(category("9c 9d Kh Ks 2c"), PokerCategory::TwoPair);
+        assert_eq!(category("9c 9d Kh Qs 2c"), PokerCategory::OnePair);
+        // Camel Cards have no suits, so no flushes either
+        assert_eq!(category("K 9 7 4 2"), PokerCategory::HighCard);
+        assert_eq!(category("7 7 7 7 7"), PokerCategory::FiveOfAKind);
+    }
+
+    #[test]
+    fn breaks_ties() {
+        let strength = |s: &str| hand(s).strength();
+
+        // the wheel is the lowest straight
+        assert!(strength("6c 5d 4h 3s 2c") > strength("5c 4d 3h 2s Ac"));
+        // the three of a kind decides a full house before the pair
+        assert!(strength("3c 3d 3h 2s 2c") > strength("2c 2d 2h As Ac"));
+        // then kickers, highest first
+        assert!(strength("9c 9d Kh Qs 3c") > strength("9h 9s Kd Qc 2d"));
+        assert_eq!(strength("9c 9d Kh Qs 3c"), strength("Kc 9h 3d 9s Qd"));
+    }
+
+    #[test]
+    fn picks_best_five_of_seven() {
+        let best = best_hand(&cards("Ah Kh 7c 7d Qh Jh Th")).unwrap();
+        assert_eq!(best.category(), PokerCategory::StraightFlush);
+        assert_eq!(best.to_string(), "StraightFlush: Ah Kh Qh Jh Th");
+
+        let best = best_hand(&cards("2c 2d 5h 9s Jc 2h 9d")).unwrap();
+        assert_eq!(best.category(), PokerCategory::FullHouse);
+
+        assert_eq!(
+            best_hand(&cards("2c 2d 5h 9s")),
+            Err(PokerError::WrongCount(4))
+        );
+        assert_eq!(
+            evaluate(&cards("2c 2c 5h 9s Jc")),
+            Err(PokerError::DuplicateCard("2c".parse().unwrap()))
+        );
+        assert!("2x".parse::<Card>().is_err() && "1".parse::<Card>().is_err());
+    }
+}
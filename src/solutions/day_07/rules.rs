@@ -0,0 +1,76 @@
+use super::Rank;
+
+/// How two hands of the same type are told apart.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum TieBreak {
+    /// Compare the first cards, then the second cards, and so on, as the puzzle does.
+    #[default]
+    InOrder,
+    /// Compare the strongest cards of each hand, then the next strongest, and so on.
+    HighestFirst,
+}
+
+/// The rules of a game of Camel Cards. The default is part 1: no wild cards, five cards a
+/// hand, aces high, and ties broken card by card.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RuleSet {
+    /// every rank in play, weakest first
+    pub order: Vec<Rank>,
+    /// ranks that count as whichever rank makes the hand strongest
+    pub wild: Vec<Rank>,
+    pub hand_size: usize,
+    pub tie_break: TieBreak,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            order: Rank::ALL.iter().rev().cloned().collect(),
+            wild: vec![],
+            hand_size: 5,
+            tie_break: TieBreak::InOrder,
+        }
+    }
+}
+
+impl RuleSet {
+    /// Part 2: jacks become jokers, which are wild but the weakest card on their own.
+    pub fn jokers() -> RuleSet {
+        let mut rules = RuleSet::default();
+        rules.order.retain(|r| r != &Rank::J);
+        rules.order.insert(0, Rank::J);
+        rules.wild = vec![Rank::J];
+
+        rules
+    }
+
+    /// How strong a card is on its own, from 1 for the weakest, or `None` if it isn't in play.
+    pub fn strength(&self, rank: &Rank) -> Option<u32> {
+        self.order
+            .iter()
+            .position(|r| r == rank)
+            .map(|i| i as u32 + 1)
+    }
+
+    pub fn is_wild(&self, rank: &Rank) -> bool {
+        self.wild.contains(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rank, RuleSet};
+
+    #[test]
+    fn jokers_are_weakest() {
+        let standard = RuleSet::default();
+        let jokers = RuleSet::jokers();
+
+        assert_eq!(standard.strength(&Rank::Two), Some(1));
+        assert_eq!(standard.strength(&Rank::J), Some(10));
+        assert_eq!(standard.strength(&Rank::A), Some(13));
+        assert_eq!(jokers.strength(&Rank::J), Some(1));
+        assert_eq!(jokers.strength(&Rank::Two), Some(2));
+        assert!(jokers.is_wild(&Rank::J) && !standard.is_wild(&Rank::J));
+    }
+}
@@ -0,0 +1,275 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::utils::cycle::{first_common_hit, Cycle};
+
+use super::Solver;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum NetworkError {
+    MissingInstructions,
+    InvalidInstruction(char),
+    InvalidNode { line: usize },
+    DuplicateNode { line: usize, node: String },
+    UnknownNode(String),
+}
+
+impl Display for NetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkError::MissingInstructions => write!(f, "expected a line of L/R instructions"),
+            NetworkError::InvalidInstruction(c) => write!(f, "invalid instruction '{}'", c),
+            NetworkError::InvalidNode { line } => {
+                write!(f, "line {}: expected 'AAA = (BBB, CCC)'", line)
+            }
+            NetworkError::DuplicateNode { line, node } => {
+                write!(f, "line {}: node {} is defined twice", line, node)
+            }
+            NetworkError::UnknownNode(node) => write!(f, "node {} is never defined", node),
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+/// The instructions and the nodes they walk through. Nodes are stored by index in the order
+/// they're defined.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Network {
+    pub instructions: Vec<Direction>,
+    names: Vec<String>,
+    // (left, right) for each node
+    links: Vec<(usize, usize)>,
+}
+
+impl Network {
+    pub fn parse(lines: &[&str]) -> Result<Network, NetworkError> {
+        let (instructions, nodes) = lines
+            .split_first()
+            .ok_or(NetworkError::MissingInstructions)?;
+        let instructions = instructions
+            .chars()
+            .map(|c| match c {
+                'L' => Ok(Direction::Left),
+                'R' => Ok(Direction::Right),
+                c => Err(NetworkError::InvalidInstruction(c)),
+            })
+            .collect::<Result<Vec<Direction>, NetworkError>>()?;
+        if instructions.is_empty() {
+            return Err(NetworkError::MissingInstructions);
+        }
+
+        let mut definitions: Vec<(&str, &str, &str)> = vec![];
+        for (i, line) in nodes.iter().enumerate() {
+            // the instructions are line 1
+            let line_num = i + 2;
+            let invalid = || NetworkError::InvalidNode { line: line_num };
+
+            let (node, links) = line.split_once('=').ok_or_else(invalid)?;
+            let (left, right) = links
+                .trim()
+                .strip_prefix('(')
+                .and_then(|l| l.strip_suffix(')'))
+                .and_then(|l| l.split_once(','))
+                .ok_or_else(invalid)?;
+
+            let (node, left, right) = (node.trim(), left.trim(), right.trim());
+            if [node, left, right].iter().any(|n| n.is_empty()) {
+                return Err(invalid());
+            }
+            if definitions.iter().any(|(n, _, _)| *n == node) {
+                return Err(NetworkError::DuplicateNode {
+                    line: line_num,
+                    node: node.to_string(),
+                });
+            }
+            definitions.push((node, left, right));
+        }
+
+        let indices: HashMap<&str, usize> = definitions
+            .iter()
+            .enumerate()
+            .map(|(i, (node, _, _))| (*node, i))
+            .collect();
+        let index = |node: &str| {
+            indices
+                .get(node)
+                .copied()
+                .ok_or_else(|| NetworkError::UnknownNode(node.to_string()))
+        };
+
+        let links = definitions
+            .iter()
+            .map(|(_, left, right)| Ok((index(left)?, index(right)?)))
+            .collect::<Result<Vec<(usize, usize)>, NetworkError>>()?;
+
+        Ok(Network {
+            instructions,
+            names: definitions.iter().map(|(n, _, _)| n.to_string()).collect(),
+            links,
+        })
+    }
+
+    pub fn node(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn name(&self, node: usize) -> &str {
+        &self.names[node]
+    }
+
+    pub fn step(&self, node: usize, direction: Direction) -> usize {
+        match direction {
+            Direction::Left => self.links[node].0,
+            Direction::Right => self.links[node].1,
+        }
+    }
+
+    /// Where following the instructions from `start` leads, forever. The state is the node
+    /// plus how far through the instructions we are, since both decide the next step.
+    pub fn walk(&self, start: usize, is_target: impl Fn(&str) -> bool) -> Cycle {
+        Cycle::detect(
+            (start, 0),
+            |&(node, i)| {
+                let next = self.step(node, self.instructions[i]);
+                (next, (i + 1) % self.instructions.len())
+            },
+            |&(node, _)| is_target(self.name(node)),
+        )
+    }
+}
+
+pub struct DayEightSolver {}
+
+impl<'a> Solver<'a> for DayEightSolver {
+    fn part_1(&self, input: &'a [&'a str]) -> Result<String, ()> {
+        let network = Network::parse(input).map_err(|_| ())?;
+        let start = network.node("AAA").ok_or(())?;
+
+        // walking until a repeat means a network that never reaches ZZZ can't loop forever
+        let steps = network
+            .walk(start, |node| node == "ZZZ")
+            .first_hit()
+            .ok_or(())?;
+
+        Ok(steps.to_string())
+    }
+
+    fn part_2(&self, input: &'a [&'a str]) -> Result<String, ()> {
+        let network = Network::parse(input).map_err(|_| ())?;
+
+        let ghosts: Vec<Cycle> = (0..network.names.len())
+            .filter(|&node| network.name(node).ends_with('A'))
+            .map(|start| network.walk(start, |node| node.ends_with('Z')))
+            .collect();
+
+        let steps = first_common_hit(&ghosts).ok_or(())?;
+
+        Ok(steps.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solutions::{normalize_input, Solver};
+
+    use super::{DayEightSolver, Network, NetworkError};
+
+    #[test]
+    fn part_1_example() {
+        let input = normalize_input(
+            "
+            RL
+
+            AAA = (BBB, CCC)
+            BBB = (DDD, EEE)
+            CCC = (ZZZ, GGG)
+            DDD = (DDD, DDD)
+            EEE = (EEE, EEE)
+            GGG = (GGG, GGG)
+            ZZZ = (ZZZ, ZZZ)
+            ",
+        )
+        .unwrap();
+        let solver = DayEightSolver {};
+
+        assert_eq!(solver.part_1(&input).unwrap(), "2");
+    }
+
+    #[test]
+    fn part_1_repeats_instructions() {
+        let input = normalize_input(
+            "
+            LLR
+
+            AAA = (BBB, BBB)
+            BBB = (AAA, ZZZ)
+            ZZZ = (ZZZ, ZZZ)
+            ",
+        )
+        .unwrap();
+        let solver = DayEightSolver {};
+
+        assert_eq!(solver.part_1(&input).unwrap(), "6");
+    }
+
+    #[test]
+    fn part_2_example() {
+        let input = normalize_input(
+            "
+            LR
+
+            11A = (11B, XXX)
+            11B = (XXX, 11Z)
+            11Z = (11B, XXX)
+            22A = (22B, XXX)
+            22B = (22C, 22C)
+            22C = (22Z, 22Z)
+            22Z = (22B, 22B)
+            XXX = (XXX, XXX)
+            ",
+        )
+        .unwrap();
+        let solver = DayEightSolver {};
+
+        assert_eq!(solver.part_2(&input).unwrap(), "6");
+    }
+
+    #[test]
+    fn unreachable_targets_are_errors() {
+        let solver = DayEightSolver {};
+
+        assert_eq!(
+            solver.part_1(&["L", "AAA = (AAA, ZZZ)", "ZZZ = (ZZZ, ZZZ)"]),
+            Err(())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_networks() {
+        let parse = |lines: &[&str]| Network::parse(lines).unwrap_err();
+
+        assert_eq!(parse(&[]), NetworkError::MissingInstructions);
+        assert_eq!(parse(&["LXR"]), NetworkError::InvalidInstruction('X'));
+        assert_eq!(
+            parse(&["L", "AAA = BBB, CCC"]),
+            NetworkError::InvalidNode { line: 2 }
+        );
+        assert_eq!(
+            parse(&["L", "AAA = (AAA, AAA)", "AAA = (AAA, AAA)"]),
+            NetworkError::DuplicateNode {
+                line: 3,
+                node: "AAA".to_string()
+            }
+        );
+        assert_eq!(
+            parse(&["L", "AAA = (AAA, BBB)"]),
+            NetworkError::UnknownNode("BBB".to_string())
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use crate::utils::sequence::{extrapolate_backward, extrapolate_forward, lagrange_at};
+
+use super::Solver;
+
+pub struct DayNineSolver {}
+
+fn parse_histories(input: &[&str]) -> Result<Vec<Vec<i64>>, ()> {
+    input
+        .iter()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|v| v.parse().map_err(|_| ()))
+                .collect()
+        })
+        .collect()
+}
+
+// adds up `predict` over every history, failing if any prediction does
+fn sum_predictions(input: &[&str], predict: impl Fn(&[i64]) -> Option<i128>) -> Result<i128, ()> {
+    parse_histories(input)?
+        .iter()
+        .try_fold(0_i128, |acc, history| acc.checked_add(predict(history)?))
+        .ok_or(())
+}
+
+impl<'a> Solver<'a> for DayNineSolver {
+    fn part_1(&self, input: &'a [&'a str]) -> Result<String, ()> {
+        let res = sum_predictions(input, extrapolate_forward)?;
+
+        Ok(res.to_string())
+    }
+
+    fn part_2(&self, input: &'a [&'a str]) -> Result<String, ()> {
+        let res = sum_predictions(input, extrapolate_backward)?;
+
+        Ok(res.to_string())
+    }
+
+    fn run_mode(&self, mode: &str, args: &[&str], input: &'a [&'a str]) -> Result<String, ()> {
+        match mode {
+            // either part by Lagrange interpolation instead: `lagrange` or `lagrange backward`
+            "lagrange" => {
+                let res = if args.contains(&"backward") {
+                    sum_predictions(input, |history| lagrange_at(history, -1))?
+                } else {
+                    sum_predictions(input, |history| lagrange_at(history, history.len() as i64))?
+                };
+
+                Ok(res.to_string())
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solutions::{normalize_input, Solver};
+
+    use super::DayNineSolver;
+
+    const INPUT: &str = "
+        0 3 6 9 12 15
+        1 3 6 10 15 21
+        10 13 16 21 30 45
+    ";
+
+    #[test]
+    fn part_1_example() {
+        let input = normalize_input(INPUT).unwrap();
+        let solver = DayNineSolver {};
+
+        assert_eq!(solver.part_1(&input).unwrap(), "114");
+        assert_eq!(solver.run_mode("lagrange", &[], &input).unwrap(), "114");
+    }
+
+    #[test]
+    fn part_2_example() {
+        let input = normalize_input(INPUT).unwrap();
+        let solver = DayNineSolver {};
+
+        assert_eq!(solver.part_2(&input).unwrap(), "2");
+        assert_eq!(
+            solver.run_mode("lagrange", &["backward"], &input).unwrap(),
+            "2"
+        );
+    }
+
+    #[test]
+    fn bad_histories_are_errors() {
+        let solver = DayNineSolver {};
+
+        assert_eq!(solver.part_1(&["1 2 x"]), Err(()));
+    }
+}
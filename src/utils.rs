@@ -0,0 +1,6 @@
+pub mod big_uint;
+pub mod cycle;
+pub mod grid;
+pub mod interval;
+pub mod range_map;
+pub mod sequence;
@@ -0,0 +1,141 @@
+use std::{
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, AddAssign},
+};
+
+/// An unsigned integer with no upper bound. Only supports what the solvers need so far:
+/// addition and printing.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct BigUint {
+    // little endian base 2^32 digits, without trailing zeros
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The value as a `u64`, if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            [lo] => Some(*lo as u64),
+            [lo, hi] => Some((*hi as u64) << 32 | *lo as u64),
+            _ => None,
+        }
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    // divides in place, returning the remainder
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem: u64 = 0;
+        for limb in self.limbs.iter_mut().rev() {
+            let cur = rem << 32 | *limb as u64;
+            *limb = (cur / divisor as u64) as u32;
+            rem = cur % divisor as u64;
+        }
+        self.trim();
+
+        rem as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        let mut n = BigUint {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        };
+        n.trim();
+        n
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        if self.limbs.len() < rhs.limbs.len() {
+            self.limbs.resize(rhs.limbs.len(), 0);
+        }
+
+        let mut carry = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + *rhs.limbs.get(i).unwrap_or(&0) as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, rhs: &BigUint) -> BigUint {
+        self += rhs;
+        self
+    }
+}
+
+impl<'a> Sum<&'a BigUint> for BigUint {
+    fn sum<I: Iterator<Item = &'a BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::default(), |acc, n| acc + n)
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // peel off nine decimal digits at a time, least significant first
+        let mut n = self.clone();
+        let mut chunks = vec![];
+        while !n.is_zero() {
+            chunks.push(n.div_rem_small(1_000_000_000));
+        }
+
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap())?;
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BigUint;
+
+    #[test]
+    fn adds_past_u64() {
+        let max = BigUint::from(u64::MAX);
+        let sum = max.clone() + &max + &BigUint::from(2);
+
+        assert_eq!(sum.to_u64(), None);
+        assert_eq!(sum.to_string(), (u64::MAX as u128 * 2 + 2).to_string());
+        assert_eq!(max.to_u64(), Some(u64::MAX));
+    }
+
+    #[test]
+    fn displays() {
+        assert_eq!(BigUint::default().to_string(), "0");
+        assert_eq!(BigUint::from(1_000_000_007).to_string(), "1000000007");
+
+        let mut n = BigUint::from(1);
+        for _ in 0..100 {
+            n = n.clone() + &n;
+        }
+        assert_eq!(n.to_string(), "1267650600228229401496703205376");
+    }
+}
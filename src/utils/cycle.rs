@@ -0,0 +1,326 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    hash::Hash,
+};
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The least common multiple, or `None` if it doesn't fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Solves `x = residue (mod modulus)` for every pair at once, returning the smallest such
+/// `x` and the modulus of all solutions, `(x, lcm of the moduli)`. The moduli don't need to be
+/// coprime; `None` if the congruences contradict each other or the modulus overflows.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    congruences
+        .iter()
+        .try_fold((0_u64, 1_u64), |(x, m), &(residue, modulus)| {
+            if modulus == 0 {
+                return None;
+            }
+            let residue = residue % modulus;
+
+            // x + m * k = residue (mod modulus), solvable if gcd(m, modulus) divides the gap
+            let g = gcd(m, modulus);
+            let gap = (residue as i128 - x as i128).rem_euclid(modulus as i128);
+            if gap % g as i128 != 0 {
+                return None;
+            }
+
+            let reduced = (modulus / g) as i128;
+            let inverse = mod_inverse((m / g) as i128 % reduced, reduced)?;
+            let k = (gap / g as i128) * inverse % reduced;
+
+            let combined = lcm(m, modulus)?;
+            let x = (x as i128 + m as i128 * k).rem_euclid(combined as i128);
+
+            Some((x as u64, combined))
+        })
+}
+
+// the inverse of `a` modulo `m`, for coprime `a` and `m`
+fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    if m == 1 {
+        return Some(0);
+    }
+
+    // extended euclid, tracking only the coefficient of `a`
+    let (mut old_r, mut r) = (a, m);
+    let (mut old_s, mut s) = (1_i128, 0_i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+
+    (old_r == 1).then(|| old_s.rem_euclid(m))
+}
+
+/// What happens when a deterministic process is run forever: after `start` steps its states
+/// repeat every `length` steps. `hits` are the steps before `start + length` at which it is
+/// on a target, which is enough to know every step it ever is.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Cycle {
+    pub start: u64,
+    pub length: u64,
+    pub hits: Vec<u64>,
+}
+
+impl Cycle {
+    /// Runs `next` from `initial` until a state comes round again, noting the steps where
+    /// `is_target` holds. The state must capture everything that decides the next state.
+    pub fn detect<S: Hash + Eq + Clone>(
+        initial: S,
+        mut next: impl FnMut(&S) -> S,
+        is_target: impl Fn(&S) -> bool,
+    ) -> Cycle {
+        let mut seen: HashMap<S, u64> = HashMap::new();
+        let mut hits = vec![];
+
+        let mut state = initial;
+        let mut step = 0;
+        loop {
+            if let Some(&start) = seen.get(&state) {
+                return Cycle {
+                    start,
+                    length: step - start,
+                    hits,
+                };
+            }
+
+            if is_target(&state) {
+                hits.push(step);
+            }
+            let following = next(&state);
+            seen.insert(state, step);
+            state = following;
+            step += 1;
+        }
+    }
+
+    pub fn is_hit(&self, step: u64) -> bool {
+        let step = if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        };
+
+        self.hits.contains(&step)
+    }
+
+    pub fn first_hit(&self) -> Option<u64> {
+        self.hits.first().copied()
+    }
+
+    // the hits that come round again every lap
+    fn periodic_hits(&self) -> impl Iterator<Item = u64> + '_ {
+        self.hits.iter().copied().filter(|&h| h >= self.start)
+    }
+
+    /// True when the target is reached exactly once a lap, first at exactly one lap in, and
+    /// never before the cycle starts. Then the hits are the multiples of the lap length, so
+    /// the lcm of the lengths is the answer.
+    pub fn is_simple(&self) -> bool {
+        matches!(self.hits.as_slice(), [hit] if *hit >= self.start && *hit == self.length)
+    }
+}
+
+// the most residues `first_common_hit` will track before giving up
+const MAX_RESIDUES: usize = 1 << 16;
+
+/// The first step at which every process is on a target at once, or `None` if that never
+/// happens (or only past `u64::MAX`). Also `None` when the processes have so many targets
+/// per lap that more than `MAX_RESIDUES` combinations of them would need tracking.
+pub fn first_common_hit(cycles: &[Cycle]) -> Option<u64> {
+    if cycles.is_empty() {
+        return None;
+    }
+
+    if cycles.iter().all(Cycle::is_simple) {
+        return cycles.iter().try_fold(1, |acc, c| lcm(acc, c.length));
+    }
+
+    // before every process has settled into its cycle there's no pattern to use, but that's
+    // at most as many steps as the slowest one took to find its cycle
+    let settled = cycles.iter().map(|c| c.start).max().unwrap();
+    let early = (0..settled).find(|&step| cycles.iter().all(|c| c.is_hit(step)));
+    if early.is_some() {
+        return early;
+    }
+
+    // after that every process hits on a few residues of its length. Fold the processes in
+    // one at a time, keeping only the distinct residues of the combined length that every
+    // process so far hits on, rather than trying every combination of hits
+    let mut residues: BTreeSet<u64> = BTreeSet::from([0]);
+    let mut modulus = 1;
+    for cycle in cycles {
+        residues = residues
+            .iter()
+            .flat_map(|&x| {
+                cycle
+                    .periodic_hits()
+                    .filter_map(move |h| crt(&[(x, modulus), (h % cycle.length, cycle.length)]))
+                    .map(|(x, _)| x)
+            })
+            .collect();
+        modulus = lcm(modulus, cycle.length)?;
+
+        if residues.is_empty() || residues.len() > MAX_RESIDUES {
+            return None;
+        }
+    }
+
+    residues
+        .iter()
+        .filter_map(|&x| {
+            // the smallest solution that's at or past `settled`
+            let laps = settled.saturating_sub(x).div_ceil(modulus);
+            x.checked_add(laps.checked_mul(modulus)?)
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crt, first_common_hit, lcm, Cycle};
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // moduli that share factors
+        assert_eq!(crt(&[(3, 4), (1, 6)]), Some((7, 12)));
+        assert_eq!(crt(&[(0, 4), (1, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn detects_cycles() {
+        // 0, 1, 2, 3, 4, 5, 3, 4, 5... with targets on the even numbers
+        let cycle = Cycle::detect(0, |&n| if n == 5 { 3 } else { n + 1 }, |&n| n % 2 == 0);
+
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 3,
+                length: 3,
+                hits: vec![0, 2, 4]
+            }
+        );
+        assert!(cycle.is_hit(7) && !cycle.is_hit(6) && !cycle.is_hit(3));
+    }
+
+    // the slow way: step every process together until they're all on a target
+    fn brute_force(cycles: &[Cycle], limit: u64) -> Option<u64> {
+        (0..limit).find(|&step| cycles.iter().all(|c| c.is_hit(step)))
+    }
+
+    #[test]
+    fn common_hits_match_brute_force() {
+        let cycles = [
+            // the simple kind, where the lcm is the answer
+            Cycle {
+                start: 2,
+                length: 4,
+                hits: vec![4],
+            },
+            Cycle {
+                start: 1,
+                length: 6,
+                hits: vec![6],
+            },
+            // offset hits, several per lap and hits before the cycle
+            Cycle {
+                start: 0,
+                length: 5,
+                hits: vec![3],
+            },
+            Cycle {
+                start: 3,
+                length: 7,
+                hits: vec![1, 4, 8],
+            },
+            Cycle {
+                start: 2,
+                length: 9,
+                hits: vec![0, 1, 5, 10],
+            },
+            // a single hit on a multiple of the length that isn't the first lap, or at step 0
+            Cycle {
+                start: 6,
+                length: 3,
+                hits: vec![6],
+            },
+            Cycle {
+                start: 0,
+                length: 4,
+                hits: vec![0],
+            },
+        ];
+        assert_eq!(first_common_hit(&cycles[..2]), Some(12));
+        assert_eq!(first_common_hit(&cycles[5..6]), Some(6));
+        assert_eq!(first_common_hit(&cycles[6..]), Some(0));
+
+        for a in 0..cycles.len() {
+            for b in 0..cycles.len() {
+                for c in 0..cycles.len() {
+                    let chosen = [cycles[a].clone(), cycles[b].clone(), cycles[c].clone()];
+                    assert_eq!(
+                        first_common_hit(&chosen),
+                        brute_force(&chosen, 10_000),
+                        "{:?}",
+                        chosen
+                    );
+                }
+            }
+        }
+
+        // two processes that are never on their targets together
+        let never = [
+            Cycle {
+                start: 0,
+                length: 2,
+                hits: vec![0],
+            },
+            Cycle {
+                start: 0,
+                length: 4,
+                hits: vec![1],
+            },
+        ];
+        assert_eq!(first_common_hit(&never), None);
+    }
+
+    #[test]
+    fn many_hits_per_lap_stay_bounded() {
+        // a hit on every step of the lap but every seventh, past the cycle's start
+        let cycles: Vec<Cycle> = [(1, 31), (2, 33), (4, 37), (3, 41), (1, 43), (2, 47)]
+            .into_iter()
+            .map(|(start, length)| Cycle {
+                start,
+                length,
+                hits: (start..start + length).filter(|h| h % 7 != 6).collect(),
+            })
+            .collect();
+
+        // three processes leave fewer than 31 * 33 * 37 residues to track
+        let few = &cycles[..3];
+        assert_eq!(first_common_hit(few), brute_force(few, 10_000));
+        assert!(first_common_hit(few).is_some());
+
+        // six would be over 10^8, so the fallback gives up rather than trying them all
+        assert_eq!(first_common_hit(&cycles), None);
+    }
+}
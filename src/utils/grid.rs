@@ -0,0 +1,287 @@
+use std::collections::{HashSet, VecDeque};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug, PartialOrd, Ord)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Point {
+        Point { x, y }
+    }
+
+    /// Moves by `(dx, dy)`, returning `None` if either coordinate would go negative.
+    pub fn offset(&self, (dx, dy): (isize, isize)) -> Option<Point> {
+        Some(Point {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Neighborhood {
+    /// up, down, left and right
+    Four,
+    /// `Four` plus the diagonals
+    Eight,
+}
+
+impl Neighborhood {
+    pub fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Neighborhood::Eight => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// A row-major 2D grid. Rows don't need to be the same length; a point is only in bounds
+/// if its own row reaches it.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Grid<T> {
+    rows: Vec<Vec<T>>,
+}
+
+impl Grid<char> {
+    pub fn from_lines(lines: &[&str]) -> Grid<char> {
+        Self::from_lines_with(lines, |c| c)
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: Vec<Vec<T>>) -> Grid<T> {
+        Grid { rows }
+    }
+
+    pub fn from_lines_with(lines: &[&str], mut f: impl FnMut(char) -> T) -> Grid<T> {
+        Grid {
+            rows: lines
+                .iter()
+                .map(|line| line.chars().map(&mut f).collect())
+                .collect(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The length of the longest row.
+    pub fn width(&self) -> usize {
+        self.rows.iter().map(|r| r.len()).max().unwrap_or(0)
+    }
+
+    pub fn row_len(&self, y: usize) -> Option<usize> {
+        self.rows.get(y).map(|r| r.len())
+    }
+
+    pub fn is_ragged(&self) -> bool {
+        self.rows.windows(2).any(|w| w[0].len() != w[1].len())
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.get(p).is_some()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.rows.get(p.y)?.get(p.x)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.rows.get_mut(p.y)?.get_mut(p.x)
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        self.rows.get(y).map(|r| r.as_slice())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.rows.iter().map(|r| r.as_slice())
+    }
+
+    /// Cells in column `x` from top to bottom, skipping rows too short to reach it.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = (Point, &T)> {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter_map(move |(y, r)| r.get(x).map(|v| (Point::new(x, y), v)))
+    }
+
+    /// Walks from `start` (inclusive) in direction `dir` until leaving the grid.
+    pub fn ray(&self, start: Point, dir: (isize, isize)) -> impl Iterator<Item = (Point, &T)> {
+        let mut next = Some(start);
+        std::iter::from_fn(move || {
+            let p = next?;
+            let v = self.get(p)?;
+            next = p.offset(dir);
+            Some((p, v))
+        })
+    }
+
+    /// The down-right diagonal starting at `start`.
+    pub fn diagonal(&self, start: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.ray(start, (1, 1))
+    }
+
+    /// The down-left diagonal starting at `start`.
+    pub fn anti_diagonal(&self, start: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.ray(start, (-1, 1))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.rows.iter().enumerate().flat_map(|(y, r)| {
+            r.iter()
+                .enumerate()
+                .map(move |(x, v)| (Point::new(x, y), v))
+        })
+    }
+
+    /// The in-bounds neighbors of `p`.
+    pub fn neighbors(
+        &self,
+        p: Point,
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = Point> + '_ {
+        neighborhood
+            .offsets()
+            .iter()
+            .filter_map(move |&d| p.offset(d))
+            .filter(|&n| self.contains(n))
+    }
+
+    /// All points reachable from `start` through cells matching `passable`, including `start`
+    /// itself. Empty if `start` isn't passable.
+    pub fn flood_fill(
+        &self,
+        start: Point,
+        neighborhood: Neighborhood,
+        passable: impl Fn(&T) -> bool,
+    ) -> HashSet<Point> {
+        let mut seen = HashSet::new();
+        if !self.get(start).is_some_and(&passable) {
+            return seen;
+        }
+
+        let mut queue = VecDeque::from([start]);
+        seen.insert(start);
+        while let Some(p) = queue.pop_front() {
+            for n in self.neighbors(p, neighborhood) {
+                if passable(&self.rows[n.y][n.x]) && seen.insert(n) {
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        seen
+    }
+
+    /// Labels each connected region of cells matching `include`. Returns a grid of the same
+    /// shape holding each cell's component index (or `None`), along with the component count.
+    /// Components are numbered in row-major order of their first cell.
+    pub fn connected_components(
+        &self,
+        neighborhood: Neighborhood,
+        include: impl Fn(&T) -> bool,
+    ) -> (Grid<Option<usize>>, usize) {
+        let mut labels = Grid {
+            rows: self.rows.iter().map(|r| vec![None; r.len()]).collect(),
+        };
+        let mut count = 0;
+
+        for (p, v) in self.iter() {
+            if labels.rows[p.y][p.x].is_some() || !include(v) {
+                continue;
+            }
+
+            for q in self.flood_fill(p, neighborhood, &include) {
+                labels.rows[q.y][q.x] = Some(count);
+            }
+            count += 1;
+        }
+
+        (labels, count)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            rows: self
+                .rows
+                .iter()
+                .map(|r| r.iter().map(&f).collect())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors_respect_ragged_rows() {
+        let grid = Grid::from_lines(&["abc", "d", "efg"]);
+
+        assert!(grid.is_ragged());
+        assert_eq!(grid.width(), 3);
+
+        let mut n: Vec<Point> = grid
+            .neighbors(Point::new(0, 1), Neighborhood::Eight)
+            .collect();
+        n.sort_by_key(|p| (p.y, p.x));
+        assert_eq!(
+            n,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(0, 2),
+                Point::new(1, 2),
+            ]
+        );
+        assert_eq!(
+            grid.neighbors(Point::new(0, 0), Neighborhood::Four).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn lines_through_the_grid() {
+        let grid = Grid::from_lines(&["abc", "def", "ghi"]);
+
+        let col: String = grid.column(1).map(|(_, c)| c).collect();
+        assert_eq!(col, "beh");
+        let diag: String = grid.diagonal(Point::new(0, 0)).map(|(_, c)| c).collect();
+        assert_eq!(diag, "aei");
+        let anti: String = grid
+            .anti_diagonal(Point::new(2, 0))
+            .map(|(_, c)| c)
+            .collect();
+        assert_eq!(anti, "ceg");
+    }
+
+    #[test]
+    fn flood_fill_and_components() {
+        let grid = Grid::from_lines(&["##..#", "#...#", ".##..", "#.###"]);
+
+        let region = grid.flood_fill(Point::new(0, 0), Neighborhood::Four, |&c| c == '#');
+        assert_eq!(region.len(), 3);
+
+        let (_, four) = grid.connected_components(Neighborhood::Four, |&c| c == '#');
+        assert_eq!(four, 4);
+        let (labels, eight) = grid.connected_components(Neighborhood::Eight, |&c| c == '#');
+        assert_eq!(eight, 2);
+        assert_eq!(labels.get(Point::new(0, 3)), Some(&Some(0)));
+        assert_eq!(labels.get(Point::new(4, 1)), Some(&Some(1)));
+        assert_eq!(labels.get(Point::new(0, 2)), Some(&None));
+    }
+}
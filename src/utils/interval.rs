@@ -0,0 +1,199 @@
+use std::ops::Range;
+
+/// A set of `u64`s stored as sorted, disjoint, non-adjacent half-open ranges.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct IntervalSet {
+    ranges: Vec<Range<u64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    /// Builds a set from any ranges, merging the ones that overlap or touch.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range<u64>>) -> IntervalSet {
+        let mut ranges: Vec<Range<u64>> = ranges.into_iter().filter(|r| !r.is_empty()).collect();
+        ranges.sort_by_key(|r| r.start);
+
+        let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+        for r in ranges {
+            match merged.last_mut() {
+                Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+                _ => merged.push(r),
+            }
+        }
+
+        IntervalSet { ranges: merged }
+    }
+
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many values are in the set.
+    pub fn count(&self) -> u128 {
+        self.ranges.iter().map(|r| (r.end - r.start) as u128).sum()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        self.ranges.last().map(|r| r.end - 1)
+    }
+
+    pub fn contains(&self, x: u64) -> bool {
+        let idx = self.ranges.partition_point(|r| r.start <= x);
+        idx > 0 && self.ranges[idx - 1].contains(&x)
+    }
+
+    pub fn insert(&mut self, range: Range<u64>) {
+        *self = self.union(&IntervalSet::from(range));
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        IntervalSet::from_ranges(self.ranges.iter().chain(&other.ranges).cloned())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let (a, b) = (&self.ranges, &other.ranges);
+        let mut ranges = vec![];
+
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            let start = a[i].start.max(b[j].start);
+            let end = a[i].end.min(b[j].end);
+            if start < end {
+                ranges.push(start..end);
+            }
+
+            // whichever range finishes first can't overlap anything else
+            if a[i].end < b[j].end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let b = &other.ranges;
+        let mut ranges = vec![];
+
+        let mut j = 0;
+        for r in &self.ranges {
+            while j < b.len() && b[j].end <= r.start {
+                j += 1;
+            }
+
+            let mut start = r.start;
+            for cut in b[j..].iter().take_while(|cut| cut.start < r.end) {
+                if cut.start > start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+            }
+            if start < r.end {
+                ranges.push(start..r.end);
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+
+    /// The set's ranges, additionally cut wherever a breakpoint falls strictly inside one.
+    /// Unlike the set itself, the pieces can be adjacent.
+    pub fn split_at(&self, breakpoints: &[u64]) -> Vec<Range<u64>> {
+        let mut breakpoints = breakpoints.to_vec();
+        breakpoints.sort();
+        breakpoints.dedup();
+
+        let mut pieces = vec![];
+        for r in &self.ranges {
+            let first = breakpoints.partition_point(|&b| b <= r.start);
+            let mut start = r.start;
+            for &b in breakpoints[first..].iter().take_while(|&&b| b < r.end) {
+                pieces.push(start..b);
+                start = b;
+            }
+            pieces.push(start..r.end);
+        }
+
+        pieces
+    }
+
+    /// Moves every value by `offset`, or `None` if any would leave the `u64` range.
+    pub fn shift(&self, offset: i128) -> Option<IntervalSet> {
+        let shift = |x: u64| u64::try_from(x as i128 + offset).ok();
+
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|r| Some(shift(r.start)?..shift(r.end - 1)?.checked_add(1)?))
+            .collect::<Option<Vec<Range<u64>>>>()?;
+
+        Some(IntervalSet { ranges })
+    }
+}
+
+impl From<Range<u64>> for IntervalSet {
+    fn from(range: Range<u64>) -> Self {
+        IntervalSet::from_ranges([range])
+    }
+}
+
+impl FromIterator<Range<u64>> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = Range<u64>>>(iter: T) -> Self {
+        IntervalSet::from_ranges(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntervalSet;
+
+    #[test]
+    fn normalizes() {
+        let set = IntervalSet::from_ranges([10..20, 0..5, 5..7, 15..25, 30..30]);
+
+        assert_eq!(set.ranges(), &[0..7, 10..25]);
+        assert_eq!(set.count(), 22);
+        assert_eq!((set.min(), set.max()), (Some(0), Some(24)));
+        assert!(set.contains(6) && !set.contains(7) && set.contains(24) && !set.contains(25));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = IntervalSet::from_ranges([0..10, 20..30]);
+        let b = IntervalSet::from_ranges([5..25, 28..40]);
+
+        let union = a.union(&b);
+        assert_eq!(union.ranges().len(), 1);
+        assert_eq!((union.min(), union.max()), (Some(0), Some(39)));
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25, 28..30]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 25..28]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20, 30..40]);
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn splits_and_shifts() {
+        let set = IntervalSet::from_ranges([0..10, 20..30]);
+
+        assert_eq!(
+            set.split_at(&[25, 5, 0, 10, 29]),
+            vec![0..5, 5..10, 20..25, 25..29, 29..30]
+        );
+        assert_eq!(set.shift(-5), None);
+        assert_eq!(set.shift(5).unwrap().ranges(), &[5..15, 25..35]);
+        assert_eq!(IntervalSet::from(u64::MAX - 1..u64::MAX).shift(1), None);
+    }
+}
@@ -0,0 +1,283 @@
+use std::ops::Range;
+
+use super::interval::IntervalSet;
+
+/// A source range that is moved, in one piece, to start at `dest`. The moved range has to fit
+/// in a `u64`, i.e. `dest + source.len()` can't overflow.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Piece {
+    pub source: Range<u64>,
+    pub dest: u64,
+}
+
+impl Piece {
+    pub fn offset(&self) -> i128 {
+        self.dest as i128 - self.source.start as i128
+    }
+
+    /// Where the piece's source range ends up.
+    pub fn image(&self) -> Range<u64> {
+        self.dest..self.dest + (self.source.end - self.source.start)
+    }
+
+    fn apply(&self, x: u64) -> u64 {
+        x - self.source.start + self.dest
+    }
+}
+
+/// A piecewise map on `u64`s where every piece shifts its range by a constant, and anything
+/// not covered by a piece maps to itself. Pieces are kept sorted and disjoint, and every
+/// piece's image fits in a `u64`, so the arithmetic on them can't overflow.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct RangeMap {
+    pieces: Vec<Piece>,
+}
+
+impl RangeMap {
+    /// Builds a map from `(source range, destination start)` entries. Where entries overlap,
+    /// the one with the lower source start wins, then the one given first.
+    ///
+    /// Panics if an entry's destination range runs past `u64::MAX`.
+    pub fn new(entries: impl IntoIterator<Item = (Range<u64>, u64)>) -> RangeMap {
+        let mut entries: Vec<(Range<u64>, u64)> = entries.into_iter().collect();
+        entries.sort_by_key(|(source, _)| source.start);
+
+        let mut covered = IntervalSet::new();
+        let mut pieces = vec![];
+        for (source, dest) in entries {
+            assert!(
+                dest.checked_add(source.end - source.start).is_some(),
+                "destination range starting at {} overflows",
+                dest
+            );
+            let uncovered = IntervalSet::from(source.clone()).difference(&covered);
+            for r in uncovered.ranges() {
+                pieces.push(Piece {
+                    source: r.clone(),
+                    dest: dest + (r.start - source.start),
+                });
+            }
+            covered.insert(source);
+        }
+        pieces.sort_by_key(|p| p.source.start);
+
+        RangeMap { pieces }
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    /// Every value where the map's offset can change: the starts and ends of all pieces.
+    pub fn breakpoints(&self) -> Vec<u64> {
+        let mut breakpoints: Vec<u64> = self
+            .pieces
+            .iter()
+            .flat_map(|p| [p.source.start, p.source.end])
+            .collect();
+        breakpoints.dedup();
+
+        breakpoints
+    }
+
+    /// A single map equivalent to applying `self` and then `next`.
+    pub fn compose(&self, next: &RangeMap) -> RangeMap {
+        // split our whole domain into runs with a single offset, including the identity gaps
+        let mut segments: Vec<Piece> = vec![];
+        let mut cursor = 0;
+        for piece in &self.pieces {
+            if piece.source.start > cursor {
+                segments.push(Piece {
+                    source: cursor..piece.source.start,
+                    dest: cursor,
+                });
+            }
+            segments.push(piece.clone());
+            cursor = piece.source.end;
+        }
+        if cursor < u64::MAX {
+            segments.push(Piece {
+                source: cursor..u64::MAX,
+                dest: cursor,
+            });
+        }
+
+        // then cut each run's image wherever `next` changes offset
+        let next_breakpoints = next.breakpoints();
+        let mut pieces: Vec<Piece> = vec![];
+        for segment in segments {
+            let len = segment.source.end - segment.source.start;
+            let image = IntervalSet::from(segment.dest..segment.dest + len);
+
+            for r in image.split_at(&next_breakpoints) {
+                let start = segment.source.start + (r.start - segment.dest);
+                let piece = Piece {
+                    source: start..start + (r.end - r.start),
+                    dest: next.map(r.start),
+                };
+                if piece.offset() == 0 {
+                    continue;
+                }
+
+                match pieces.last_mut() {
+                    Some(last)
+                        if last.source.end == piece.source.start
+                            && last.offset() == piece.offset() =>
+                    {
+                        last.source.end = piece.source.end
+                    }
+                    _ => pieces.push(piece),
+                }
+            }
+        }
+
+        RangeMap { pieces }
+    }
+
+    fn find_piece(&self, x: u64) -> Option<&Piece> {
+        let idx = self.pieces.partition_point(|p| p.source.start <= x);
+        self.pieces[..idx].last().filter(|p| p.source.contains(&x))
+    }
+
+    pub fn map(&self, x: u64) -> u64 {
+        self.find_piece(x).map_or(x, |p| p.apply(x))
+    }
+
+    /// Maps every value in `set` at once.
+    pub fn map_set(&self, set: &IntervalSet) -> IntervalSet {
+        let sources = IntervalSet::from_ranges(self.pieces.iter().map(|p| p.source.clone()));
+        let mut mapped = set.difference(&sources).ranges().to_vec();
+
+        for piece in &self.pieces {
+            let hit = set.intersection(&IntervalSet::from(piece.source.clone()));
+            mapped.extend(
+                hit.ranges()
+                    .iter()
+                    .map(|r| piece.apply(r.start)..piece.apply(r.end - 1) + 1),
+            );
+        }
+
+        IntervalSet::from_ranges(mapped)
+    }
+
+    /// Every value that maps to `y`, in no particular order.
+    pub fn sources_of(&self, y: u64) -> impl Iterator<Item = u64> + '_ {
+        let identity = self.find_piece(y).is_none().then_some(y);
+
+        self.pieces
+            .iter()
+            .filter(move |p| p.image().contains(&y))
+            .map(move |p| p.source.start + (y - p.dest))
+            .chain(identity)
+    }
+
+    /// Every value that maps into `set`. Several pieces, or a piece and an identity gap, can
+    /// land on the same value, so the result may be larger than `set`.
+    pub fn preimage(&self, set: &IntervalSet) -> IntervalSet {
+        let sources = IntervalSet::from_ranges(self.pieces.iter().map(|p| p.source.clone()));
+        let mut found = set.difference(&sources).ranges().to_vec();
+
+        for piece in &self.pieces {
+            let hit = set.intersection(&IntervalSet::from(piece.image()));
+            let back = hit
+                .shift(-piece.offset())
+                .expect("the image of a piece maps back onto its source");
+            found.extend(back.ranges().iter().cloned());
+        }
+
+        IntervalSet::from_ranges(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::interval::IntervalSet;
+
+    use super::{Piece, RangeMap};
+
+    #[test]
+    fn maps_values() {
+        let map = RangeMap::new([(98..100, 50), (50..98, 52)]);
+
+        assert_eq!(map.map(0), 0);
+        assert_eq!(map.map(50), 52);
+        assert_eq!(map.map(97), 99);
+        assert_eq!(map.map(98), 50);
+        assert_eq!(map.map(100), 100);
+    }
+
+    #[test]
+    fn overlapping_entries_resolve_to_first_match() {
+        let map = RangeMap::new([(5..15, 100), (0..10, 200)]);
+
+        assert_eq!(
+            map.pieces(),
+            &[
+                Piece {
+                    source: 0..10,
+                    dest: 200
+                },
+                Piece {
+                    source: 10..15,
+                    dest: 105
+                }
+            ]
+        );
+        assert_eq!(map.map(7), 207);
+        assert_eq!(map.map(12), 107);
+    }
+
+    #[test]
+    fn composes() {
+        let first = RangeMap::new([(98..100, 50), (50..98, 52)]);
+        let second = RangeMap::new([(0..15, 39), (15..52, 0), (52..54, 37)]);
+
+        let composed = first.compose(&second);
+        for x in 0..120 {
+            assert_eq!(composed.map(x), second.map(first.map(x)));
+        }
+        assert_eq!(composed.map(u64::MAX - 1), u64::MAX - 1);
+        assert_eq!(composed.breakpoints(), vec![0, 15, 50, 52, 98, 100]);
+
+        let identity = RangeMap::new([(10..20, 30), (30..40, 10), (20..30, 20)]);
+        assert_eq!(identity.compose(&identity), RangeMap::default());
+    }
+
+    #[test]
+    fn maps_whole_sets() {
+        let map = RangeMap::new([(98..100, 50), (50..98, 52)]);
+        let seeds = IntervalSet::from_ranges([79..93, 55..68, 95..105]);
+
+        let mapped = map.map_set(&seeds);
+        assert_eq!(mapped.ranges(), &[50..52, 57..70, 81..95, 97..105]);
+        assert_eq!(mapped.count(), seeds.count());
+        for x in (40..110).filter(|&x| seeds.contains(x)) {
+            assert!(mapped.contains(map.map(x)));
+        }
+    }
+
+    #[test]
+    fn inverts() {
+        let map = RangeMap::new([(98..100, 50), (50..98, 52), (10..12, 60)]);
+        let targets = IntervalSet::from_ranges([49..53, 60..62, 99..101]);
+
+        let sources = map.preimage(&targets);
+        for x in 0..120 {
+            assert_eq!(sources.contains(x), targets.contains(map.map(x)), "{}", x);
+        }
+        // 60 is reached from two pieces, 49 only from its own identity gap
+        assert_eq!(
+            map.preimage(&IntervalSet::from(60..61)).ranges(),
+            &[10..11, 58..59]
+        );
+        assert_eq!(
+            map.preimage(&IntervalSet::from(49..51)).ranges(),
+            &[49..50, 98..99]
+        );
+
+        let mut sources: Vec<u64> = map.sources_of(60).collect();
+        sources.sort();
+        assert_eq!(sources, vec![10, 58]);
+        assert_eq!(map.sources_of(49).collect::<Vec<_>>(), vec![49]);
+    }
+}
@@ -0,0 +1,247 @@
+//! Extrapolating sequences that follow a polynomial, like `1 4 9 16`, from their values at
+//! `0, 1, 2...`.
+//!
+//! Everything is tried with checked `i64` arithmetic first, then again in `i128` if that
+//! overflows, so results are `i128`. `None` means even `i128` wasn't enough.
+
+// the integer operations extrapolation needs, so it can run on `i64` or `i128`
+trait Checked: Copy + Eq {
+    const ZERO: Self;
+    const ONE: Self;
+    fn from_usize(n: usize) -> Option<Self>;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+}
+
+impl Checked for i64 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn from_usize(n: usize) -> Option<Self> {
+        n.try_into().ok()
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        i64::checked_add(self, other)
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        i64::checked_sub(self, other)
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        i64::checked_mul(self, other)
+    }
+
+    fn checked_div(self, other: Self) -> Option<Self> {
+        i64::checked_div(self, other)
+    }
+}
+
+impl Checked for i128 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn from_usize(n: usize) -> Option<Self> {
+        n.try_into().ok()
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        i128::checked_add(self, other)
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        i128::checked_sub(self, other)
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        i128::checked_mul(self, other)
+    }
+
+    fn checked_div(self, other: Self) -> Option<Self> {
+        i128::checked_div(self, other)
+    }
+}
+
+// the sequence, then its differences, then their differences... until a row is all zeros
+// or has a single value left
+fn table<T: Checked>(seq: &[T]) -> Option<Vec<Vec<T>>> {
+    let mut rows = vec![seq.to_vec()];
+    loop {
+        let last = rows.last().unwrap();
+        if last.len() <= 1 || last.iter().all(|&v| v == T::ZERO) {
+            return Some(rows);
+        }
+
+        let next = last
+            .windows(2)
+            .map(|w| w[1].checked_sub(w[0]))
+            .collect::<Option<Vec<T>>>()?;
+        rows.push(next);
+    }
+}
+
+fn forward<T: Checked>(seq: &[T]) -> Option<T> {
+    // each row's next value is its last value plus the next value of the row below
+    table(seq)?.iter().rev().try_fold(T::ZERO, |below, row| {
+        row.last().map_or(Some(below), |&v| v.checked_add(below))
+    })
+}
+
+fn backward<T: Checked>(seq: &[T]) -> Option<T> {
+    // and its previous value is its first value minus the previous value of the row below
+    table(seq)?.iter().rev().try_fold(T::ZERO, |below, row| {
+        row.first().map_or(Some(below), |&v| v.checked_sub(below))
+    })
+}
+
+// runs `small` on the `i64`s, and `big` on them as `i128`s if that overflows
+fn with_fallback(
+    seq: &[i64],
+    small: impl Fn(&[i64]) -> Option<i64>,
+    big: impl Fn(&[i128]) -> Option<i128>,
+) -> Option<i128> {
+    small(seq).map(i128::from).or_else(|| {
+        let seq: Vec<i128> = seq.iter().map(|&v| v as i128).collect();
+        big(&seq)
+    })
+}
+
+/// The finite difference table: the sequence, its differences, and so on down to the first
+/// row that's all zeros (or a single value, if the sequence is too short to get there).
+pub fn difference_table(seq: &[i64]) -> Option<Vec<Vec<i128>>> {
+    let seq: Vec<i128> = seq.iter().map(|&v| v as i128).collect();
+    table(&seq)
+}
+
+/// The value after the last one.
+pub fn extrapolate_forward(seq: &[i64]) -> Option<i128> {
+    with_fallback(seq, forward, forward)
+}
+
+/// The value before the first one.
+pub fn extrapolate_backward(seq: &[i64]) -> Option<i128> {
+    with_fallback(seq, backward, backward)
+}
+
+/// The degree of the lowest polynomial that fits, if the sequence is long enough to tell:
+/// it needs at least one more value than the degree plus one. All zeros has no degree.
+pub fn degree(seq: &[i64]) -> Option<usize> {
+    let rows = difference_table(seq)?;
+    let last = rows.last()?;
+    if !last.iter().all(|&v| v == 0) || rows.len() < 2 {
+        return None;
+    }
+
+    // the row above the zeros is constant, and it's the degree-th differences
+    rows.len().checked_sub(2)
+}
+
+// C(y, k) = y (y - 1) ... (y - k + 1) / k! for any integer `y`. After `m` factors the running
+// value is C(y, m), so every division is exact and nothing grows past the answer's size.
+fn binomial<T: Checked>(y: T, k: usize) -> Option<T> {
+    (0..k).try_fold(T::ONE, |c, m| {
+        let m = T::from_usize(m)?;
+        c.checked_mul(y.checked_sub(m)?)?
+            .checked_div(m.checked_add(T::ONE)?)
+    })
+}
+
+fn lagrange<T: Checked>(seq: &[T], x: T) -> Option<T> {
+    let n = seq.len();
+
+    seq.iter().enumerate().try_fold(T::ZERO, |acc, (i, &v)| {
+        // L_i(x) = prod over j != i of (x - j) / (i - j). The factors with j < i make C(x, i)
+        // and those with j > i make C(x - i - 1, n - 1 - i), negated n - 1 - i times.
+        let later = n - 1 - i;
+        let mut basis =
+            binomial(x, i)?.checked_mul(binomial(x.checked_sub(T::from_usize(i + 1)?)?, later)?)?;
+        if later % 2 == 1 {
+            basis = T::ZERO.checked_sub(basis)?;
+        }
+
+        acc.checked_add(v.checked_mul(basis)?)
+    })
+}
+
+/// The same value as `extrapolate_forward` (`x = seq.len()`) or `extrapolate_backward`
+/// (`x = -1`), found by evaluating the Lagrange interpolating polynomial through every point
+/// instead of building a difference table. With integer points the basis polynomials are
+/// binomial coefficients at whole `x`, so this stays exact.
+pub fn lagrange_at(seq: &[i64], x: i64) -> Option<i128> {
+    with_fallback(seq, |seq| lagrange(seq, x), |seq| lagrange(seq, x as i128))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{degree, difference_table, extrapolate_backward, extrapolate_forward, lagrange_at};
+
+    #[test]
+    fn extrapolates() {
+        let seq = [10, 13, 16, 21, 30, 45];
+
+        assert_eq!(
+            difference_table(&seq).unwrap(),
+            vec![
+                vec![10, 13, 16, 21, 30, 45],
+                vec![3, 3, 5, 9, 15],
+                vec![0, 2, 4, 6],
+                vec![2, 2, 2],
+                vec![0, 0],
+            ]
+        );
+        assert_eq!(extrapolate_forward(&seq), Some(68));
+        assert_eq!(extrapolate_backward(&seq), Some(5));
+        assert_eq!(degree(&seq), Some(3));
+    }
+
+    #[test]
+    fn degrees() {
+        assert_eq!(degree(&[7, 7, 7]), Some(0));
+        assert_eq!(degree(&[1, 4, 9, 16]), Some(2));
+        // a line through two points could just as well be anything else
+        assert_eq!(degree(&[1, 4]), None);
+        assert_eq!(degree(&[0, 0]), None);
+        assert_eq!(degree(&[]), None);
+    }
+
+    #[test]
+    fn lagrange_agrees_with_differences() {
+        // cubics with a mix of signs, sampled at 0..8
+        for a in -3..=3 {
+            for b in [-5, 0, 2] {
+                let seq: Vec<i64> = (0..8)
+                    .map(|x| a * x * x * x + b * x * x - 7 * x + 4)
+                    .collect();
+
+                assert_eq!(lagrange_at(&seq, 8), extrapolate_forward(&seq));
+                assert_eq!(lagrange_at(&seq, -1), extrapolate_backward(&seq));
+            }
+        }
+    }
+
+    #[test]
+    fn lagrange_handles_long_histories() {
+        // a running product of the basis factors would overflow an i128 long before 40 values
+        let seq: Vec<i64> = (0..40)
+            .map(|x| x * x * x * x * x - 3 * x * x * x + 7)
+            .collect();
+
+        assert_eq!(lagrange_at(&seq, 40), extrapolate_forward(&seq));
+        assert_eq!(lagrange_at(&seq, -1), extrapolate_backward(&seq));
+        assert_eq!(lagrange_at(&seq, -1), Some(9));
+    }
+
+    #[test]
+    fn falls_back_to_i128() {
+        // the differences of these overflow an i64, and so does the next value
+        let seq = [i64::MIN, i64::MAX, i64::MIN];
+
+        let next = extrapolate_forward(&seq).unwrap();
+        assert_eq!(next, 4 * i64::MIN as i128 - 3 * i64::MAX as i128);
+        assert_eq!(lagrange_at(&seq, 3), Some(next));
+        assert_eq!(extrapolate_forward(&[1, 2, 3]), Some(4));
+    }
+}